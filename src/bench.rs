@@ -0,0 +1,101 @@
+use std::{path::Path, time::Instant};
+
+use glob::glob;
+
+use crate::{cell::Map, solver::Strategy};
+
+const RUNS: u32 = 20; // how many times every small map is colored, to get a stable time
+
+pub fn grid(width: usize, height: usize) -> Map {
+    // a map of width * height square regions, where every square also touches
+    // the square diagonally down and to the right (so it needs 3 colors)
+    let mut map = Map::default();
+    for y in 0..height {
+        for x in 0..width {
+            map.add_cell(format!("{},{}", x, y));
+        }
+    }
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let mut link = |j: usize| {
+                map.0[i].connections.push(j);
+                map.0[j].connections.push(i);
+            };
+            if x + 1 < width {
+                link(i + 1);
+            }
+            if y + 1 < height {
+                link(i + width);
+            }
+            if x + 1 < width && y + 1 < height {
+                link(i + width + 1);
+            }
+        }
+    }
+    map
+}
+
+fn time(strategy: Strategy, map: &Map, runs: u32) -> String {
+    // the average time the solver takes, and whether it worked
+    let solver = strategy.solver();
+    let start = Instant::now();
+    let mut solution = solver.solve(map, 4);
+    for _ in 1..runs {
+        solution = solver.solve(map, 4);
+    }
+    let ms = start.elapsed().as_secs_f64() * 1000. / runs as f64;
+    format!(
+        "{:>10.3} ms {:>8} nodes{}",
+        ms,
+        solution.report.iterations,
+        if solution.report.colored {
+            ""
+        } else {
+            " (failed)"
+        }
+    )
+}
+
+pub fn run() {
+    // compare the original DFS with the iterative engine on the shipped maps
+    // and on bigger generated ones. run it with `cargo run --release -- bench`
+    let mut maps: Vec<(String, Map)> = glob("./maps/*.toml")
+        .unwrap()
+        .filter_map(|t| t.ok())
+        .filter_map(|p| match Map::from_file(Path::new(&p)) {
+            Ok(map) => Some((p.display().to_string(), map)),
+            Err(err) => {
+                println!("skipping {}: {}", p.display(), err);
+                None
+            }
+        })
+        .collect();
+    for size in [10, 20] {
+        maps.push((format!("grid {}x{}", size, size), grid(size, size)));
+    }
+    println!(
+        "{:<20} {:>6} {:>32} {:>32}",
+        "map", "cells", "DFS", "engine"
+    );
+    for (name, map) in maps.iter() {
+        println!(
+            "{:<20} {:>6} {:>32} {:>32}",
+            name,
+            map.0.len(),
+            time(Strategy::Dfs, map, RUNS),
+            time(Strategy::Engine, map, RUNS)
+        );
+    }
+    // the original search takes far too long on these, so only the engine runs
+    for size in [50, 100] {
+        let map = grid(size, size);
+        println!(
+            "{:<20} {:>6} {:>32} {:>32}",
+            format!("grid {}x{}", size, size),
+            map.0.len(),
+            "-",
+            time(Strategy::Engine, &map, 1)
+        );
+    }
+}
@@ -1,17 +1,18 @@
-use std::{collections::HashMap, fs::File, io::Read, path::Path};
+use std::{collections::HashMap, path::Path};
 
-use egui::Color32;
 use serde::{Deserialize, Serialize};
 
-const COLORS: [Color32; 4] = [
-    // these are the colours that will be displayed
-    Color32::RED,
-    Color32::GREEN,
-    Color32::BLUE,
-    Color32::from_rgb(255, 0, 255),
-];
+use crate::{
+    error::MapError,
+    format,
+    palette::{color_at, Rgb},
+    pin,
+    solver::control::Control,
+    trace::{Step, Trace},
+    validation::ValidationReport,
+};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct Map(pub Vec<Cell>); // a map which is a wrapper for a list of cells
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -19,14 +20,47 @@ pub struct Cell {
     pub name: String,            // the name of the region
     pub connections: Vec<usize>, // the neighbors/connections of the region
     pub color: Option<usize>,    // the color of the region (if any)
+    #[serde(default)]
+    pub label: Option<String>, // a longer name to show instead of the name (if any)
+    #[serde(default)]
+    pub description: Option<String>, // a description of the region (if any)
+    #[serde(default)]
+    pub pinned: Option<usize>, // a color the region must always have (if any)
 }
 
-impl Default for Map {
-    fn default() -> Map {
-        Map(Vec::new()) // create a default map
+#[derive(Clone, Debug)]
+pub struct ComponentResult {
+    pub cells: Vec<usize>, // the indices of the cells in this component
+    pub colored: bool,     // whether every cell in the component got a valid color
+    pub iterations: usize, // how many iterations it took to color this component
+    pub backtracks: usize, // how many colors had to be taken back (if the solver counts them)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ColorReport {
+    pub colored: bool,                    // true if every component was colored
+    pub iterations: usize,                // the total iterations over all components
+    pub backtracks: usize,                // the total backtracks over all components
+    pub components: Vec<ComponentResult>, // the result for each connected component
+    pub stopped: bool, // true if the search was cancelled or ran out of time before it was sure
+    pub gave_up: bool, // true if a solver that cant prove anything failed (it may still be colorable)
+}
+
+impl ColorReport {
+    pub fn unknown(&self) -> bool {
+        // true if the map wasnt colored, but that doesnt mean it cant be
+        !self.colored && (self.stopped || self.gave_up)
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct Chromatic {
+    pub colors: usize,        // the minimum number of colors the map needs
+    pub coloring: Vec<usize>, // a coloring of every cell with that many colors (the witness)
+    pub clique: Vec<usize>, // cells that all border each other, so at least clique.len() colors are needed
+    pub iterations: usize,  // the total iterations over every k that was tried
+}
+
 impl Map {
     pub fn add_names(&mut self, names: Vec<String>) {
         // add various cells given their names.
@@ -37,43 +71,322 @@ impl Map {
     pub fn add_cell(&mut self, name: String) {
         self.0.push(Cell::new(name)) // add a cell to the map, given its name
     }
-    pub fn color_map(&mut self) -> (bool, usize) {
-        // start coloring the map
-        // returns false if the map was failed to be colored.
-        let start = self.0[0].clone();
-        let mut x = 0;
-        let res = start.color_in(0, self, &mut x);
-        (res, x)
+    pub fn color_map(&mut self, k: usize) -> ColorReport {
+        self.color_map_with(k, &mut Trace::off(), &Control::default())
     }
-    pub fn validate(&self) -> bool {
-        // returns true if all connections are valid
+    pub fn trace(&self, k: usize, limit: usize) -> (ColorReport, Trace) {
+        self.trace_with(k, limit, &Control::default())
+    }
+    pub fn trace_with(&self, k: usize, limit: usize, control: &Control) -> (ColorReport, Trace) {
+        // color a blank copy of the map (but for the pins) and record every step the search takes,
+        // the search stops once limit steps were recorded or the control says so
+        let mut map = self.clone();
+        map.reset_colors();
+        let mut trace = Trace::on(limit);
+        let report = map.color_map_with(k, &mut trace, control);
+        (report, trace)
+    }
+    pub fn color_map_with(
+        &mut self,
+        k: usize,
+        trace: &mut Trace,
+        control: &Control,
+    ) -> ColorReport {
+        // start coloring the map with k colors, one connected component at a time
+        // so that islands with no links are colored as well. every step is written
+        // to the trace, and the search gives up once the control says to stop
+        let mut report = ColorReport {
+            colored: true,
+            ..Default::default()
+        };
+        // pinned cells keep their color, which the search never changes
+        for cell in self.0.iter_mut() {
+            if cell.pinned.is_some() {
+                cell.color = cell.pinned;
+            }
+        }
+        if !pin::problems(self, k).is_empty() {
+            report.colored = false;
+            return report;
+        }
+        for cells in self.components() {
+            // start the recursion from the first cell of the component. colored
+            // (pinned) cells stop the recursion, so start again from any cell
+            // that it didnt reach
+            let mut x = 0;
+            let mut res = true;
+            while let Some(i) = cells.iter().copied().find(|i| self.0[*i].color.is_none()) {
+                let start = self.0[i].clone();
+                if !start.color_in(i, self, k, &mut x, trace, control) {
+                    res = false;
+                    break;
+                }
+            }
+            // make sure the recursion actually reached and colored every cell
+            let colored = res && cells.iter().all(|i| self.is_valid_at(*i));
+            report.colored &= colored;
+            report.iterations += x;
+            report.components.push(ComponentResult {
+                cells,
+                colored,
+                iterations: x,
+                backtracks: 0,
+            });
+        }
+        // if it had to stop, not being colored doesnt mean it cant be. the search
+        // also misses colorings it doesnt backtrack far enough to find, so it never proves anything
+        report.stopped = !report.colored && (control.stopped() || trace.truncated);
+        report.gave_up = !report.colored && !report.stopped;
+        report
+    }
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        // find all connected components of the map (each sorted by index)
+        let mut seen = vec![false; self.0.len()];
+        let mut components = Vec::new();
+        for i in 0..self.0.len() {
+            if seen[i] {
+                continue;
+            }
+            // walk every cell reachable from i
+            seen[i] = true;
+            let mut stack = vec![i];
+            let mut cells = Vec::new();
+            while let Some(j) = stack.pop() {
+                cells.push(j);
+                for n in &self.0[j].connections {
+                    if !seen[*n] {
+                        seen[*n] = true;
+                        stack.push(*n);
+                    }
+                }
+            }
+            cells.sort_unstable();
+            components.push(cells);
+        }
+        components
+    }
+    pub fn chromatic_number(&self) -> Chromatic {
+        self.chromatic_number_with(&Control::default())
+            .expect("the search only stops when the control says so")
+    }
+    pub fn chromatic_number_with(&self, control: &Control) -> Option<Chromatic> {
+        // find the smallest k the map can be colored with, ignoring any pins.
+        // every component is searched on its own, starting at the size of the
+        // largest clique since fewer colors than that can never work.
+        // gives None if the control stopped the search before it knew
+        let clique = self.largest_clique(control);
+        let mut map = self.clone();
+        map.clear_colors();
+        let mut chromatic = Chromatic {
+            colors: 0,
+            coloring: Vec::new(),
+            clique,
+            iterations: 0,
+        };
+        for cells in self.components() {
+            let order = map.search_order(&cells);
+            let mut k = chromatic.clique.len().max(chromatic.colors).max(1);
+            // every k below the answer is disproved by an exhaustive search
+            while !map.backtrack(&order, 0, k, 0, &mut chromatic.iterations, control) {
+                if control.stopped() {
+                    return None;
+                }
+                k += 1;
+            }
+            chromatic.colors = k;
+        }
+        if control.stopped() {
+            return None;
+        }
+        chromatic.coloring = map.0.iter().map(|c| c.color.unwrap_or(0)).collect();
+        Some(chromatic)
+    }
+    fn search_order(&self, cells: &[usize]) -> Vec<usize> {
+        // visit the component breadth first, so every cell after the first
+        // already has a colored neighbor when it is reached
+        let mut order = vec![cells[0]];
+        let mut seen = vec![false; self.0.len()];
+        seen[cells[0]] = true;
+        let mut pos = 0;
+        while pos < order.len() {
+            for n in &self.0[order[pos]].connections {
+                if !seen[*n] {
+                    seen[*n] = true;
+                    order.push(*n);
+                }
+            }
+            pos += 1;
+        }
+        order
+    }
+    fn backtrack(
+        &mut self,
+        order: &[usize],
+        pos: usize,
+        k: usize,
+        used: usize,
+        count: &mut usize,
+        control: &Control,
+    ) -> bool {
+        // exhaustively try every color for the cell at order[pos] and
+        // everything after it. returns false only if no coloring exists,
+        // or once the control says to stop
+        *count += 1;
+        if pos == order.len() {
+            return true;
+        }
+        if control.stopped() {
+            return false;
+        }
+        let i = order[pos];
+        // colors are interchangeable, so only one unused color needs trying
+        for c in 0..k.min(used + 1) {
+            if self.0[i]
+                .connections
+                .iter()
+                .any(|n| self.0[*n].color == Some(c))
+            {
+                continue;
+            }
+            self.0[i].color = Some(c);
+            if self.backtrack(order, pos + 1, k, used.max(c + 1), count, control) {
+                return true;
+            }
+        }
+        // none of the colors worked, undo this cell
+        self.0[i].color = None;
+        false
+    }
+    pub fn max_clique(&self) -> Vec<usize> {
+        self.largest_clique(&Control::default())
+    }
+    fn largest_clique(&self, control: &Control) -> Vec<usize> {
+        // find the largest set of cells that all border each other,
+        // or the largest found before the control said to stop
+        let mut best = Vec::new();
+        let all: Vec<usize> = (0..self.0.len()).collect();
+        self.extend_clique(&mut Vec::new(), all, &mut best, control);
+        best.sort_unstable();
+        best
+    }
+    fn extend_clique(
+        &self,
+        clique: &mut Vec<usize>,
+        candidates: Vec<usize>,
+        best: &mut Vec<usize>,
+        control: &Control,
+    ) {
+        // grow the clique with every candidate that borders all of it
+        if clique.len() > best.len() {
+            *best = clique.clone();
+        }
+        for (j, c) in candidates.iter().enumerate() {
+            // stop when even taking every remaining candidate cant beat the best
+            if clique.len() + candidates.len() - j <= best.len() || control.stopped() {
+                return;
+            }
+            let next = candidates[j + 1..]
+                .iter()
+                .filter(|n| self.0[*c].connections.contains(n))
+                .cloned()
+                .collect();
+            clique.push(*c);
+            self.extend_clique(clique, next, best, control);
+            clique.pop();
+        }
+    }
+    pub fn clear_colors(&mut self) {
+        // remove the color of every cell
+        for cell in self.0.iter_mut() {
+            cell.color = None;
+        }
+    }
+    pub fn reset_colors(&mut self) {
+        // remove every color except the pinned ones, to start solving from
+        for cell in self.0.iter_mut() {
+            cell.color = cell.pinned;
+        }
+    }
+    pub fn is_valid_at(&self, i: usize) -> bool {
+        // returns true if the cell is colored and no neighbor shares its color
+        match self.0[i].color {
+            Some(c) => self.0[i]
+                .connections
+                .iter()
+                .all(|n| self.0[*n].color != Some(c)),
+            None => false,
+        }
+    }
+    pub fn conflicts(&self) -> Vec<(usize, usize)> {
+        // every link between two cells of the same color, each listed once
+        let mut conflicts = Vec::new();
         for (i, cell) in self.0.iter().enumerate() {
-            for conn in &cell.connections {
-                if !self.0[*conn].connections.contains(&i) {
-                    println!("{} {:?} {}", i, cell, conn);
-                    return false;
+            for j in cell.connections.iter().filter(|j| **j > i) {
+                if cell.color.is_some() && self.0[*j].color == cell.color {
+                    conflicts.push((i, *j));
                 }
             }
         }
-        true
+        conflicts
     }
-    pub fn from_file(file: &Path) -> Map {
-        // create a map from a file path
-        let mut s = String::new();
-        File::open(file)
-            .unwrap()
-            .read_to_string(&mut s)
-            .expect("Reading file failed!");
-
-        // parse file contents into a hashmap
-        let v: HashMap<String, Vec<String>> =
-            toml::from_str(s.as_str()).expect("Could not parse file value!");
-        match v.try_into() {
-            // try converting it into a map
-            Ok(x) => x,                    // return the valid map
-            Err(err) => panic!("{}", err), // panic if it cant be converted
+    pub fn validate(&self) -> bool {
+        // returns true if all connections are valid
+        self.check().is_ok()
+    }
+    pub fn check(&self) -> Result<(), MapError> {
+        // makes sure every name is unique and every connection goes both ways,
+        // the error is the first problem in the validation report
+        match self.validation_report().issues.into_iter().next() {
+            Some(issue) => Err(issue.into()),
+            None => Ok(()),
         }
     }
+    pub fn from_file(file: &Path) -> Result<Map, MapError> {
+        // create a map from a file path, the map has to be valid as it is
+        Ok(Map::from_file_with(file, false)?.0)
+    }
+    pub fn from_dimacs(s: &str) -> Result<Map, MapError> {
+        // read a DIMACS .col graph, the regions are called v1 to vN
+        let map = format::dimacs::read(s)?;
+        map.check()?;
+        Ok(map)
+    }
+    pub fn to_dimacs(&self) -> String {
+        // write the map as a DIMACS .col graph
+        format::dimacs::write(self, "")
+    }
+    pub fn from_json(s: &str) -> Result<Map, MapError> {
+        // read a map from JSON, in any of the layouts format::json::read knows
+        let (map, _) = format::json::read(s)?;
+        map.check()?;
+        Ok(map)
+    }
+    pub fn to_json(&self) -> Result<String, MapError> {
+        // write the cells of the map as JSON (names, connections and colors)
+        format::json::write_cells(self)
+    }
+    pub fn from_csv_edge_list(s: &str) -> Result<Map, MapError> {
+        // read a CSV with one link per line (region_a,region_b)
+        let map = format::csv::read_edge_list(s)?;
+        map.check()?;
+        Ok(map)
+    }
+    pub fn from_csv_matrix(s: &str) -> Result<Map, MapError> {
+        // read a square CSV adjacency matrix with the region names in the header row
+        let map = format::csv::read_matrix(s)?;
+        map.check()?;
+        Ok(map)
+    }
+    pub fn to_dot(&self, name: &str) -> String {
+        // write the map as a Graphviz graph, filled with the colors of the cells
+        format::dot::write(self, name)
+    }
+    pub fn from_file_with(file: &Path, repair: bool) -> Result<(Map, ValidationReport), MapError> {
+        // create a map from a file path. with repair on, link problems are fixed
+        // and returned as warnings, otherwise the first problem is the error.
+        let loaded = format::load(file, repair)?;
+        Ok((loaded.map, loaded.report))
+    }
 }
 
 impl Cell {
@@ -83,6 +396,9 @@ impl Cell {
             name,
             connections: Vec::new(),
             color: None,
+            label: None,
+            description: None,
+            pinned: None,
         }
     }
     pub fn link_changed(&mut self, other: usize) {
@@ -97,28 +413,46 @@ impl Cell {
         // if it wasnt found we have to add it
         self.connections.push(other);
     }
-    pub fn color(&self) -> Color32 {
+    pub fn color(&self) -> Rgb {
         // give the actuall color or a default color
         if let Some(c) = self.color {
-            COLORS[c] // actual color
+            color_at(c) // actual color
         } else {
-            Color32::BLACK // default color
+            Rgb::BLACK // default color
         }
     }
-    pub fn color_in(&self, i: usize, map: &mut Map, count: &mut usize) -> bool {
-        // i is the position in the map of the current item
+    pub fn color_in(
+        &self,
+        i: usize,
+        map: &mut Map,
+        k: usize,
+        count: &mut usize,
+        trace: &mut Trace,
+        control: &Control,
+    ) -> bool {
+        // i is the position in the map of the current item, k the number of colors,
+        // every color given and taken back is written to the trace
         *count += 1;
+        if trace.truncated || control.poll(*count, || map.0.iter().map(|c| c.color).collect()) {
+            return false; // unwind the whole search, also once the trace has no room left
+        }
 
         if self.color.is_some() {
             // if its already colored, we continue
             return true;
         }
         // get all avalible colors for the current cell.
-        let mut avalible = self.get_avalible(map);
+        let mut avalible = self.get_avalible(map, k);
+        if avalible.is_empty() {
+            trace.push(Step::Conflict(i));
+        }
         // try every avalible color to see if they work
         while !avalible.is_empty() {
             map.0[i].color = avalible.pop(); // gets the next color
-                                             // get all connected cells and their index
+            if let Some(c) = map.0[i].color {
+                trace.push(Step::Assign(i, c));
+            }
+            // get all connected cells and their index
             let connected_cells: Vec<(Cell, usize)> = self
                 .connections
                 .iter()
@@ -130,7 +464,7 @@ impl Cell {
 
             for (cell, j) in connected_cells {
                 // iterate through the neighbors
-                if !cell.color_in(j, map, count) {
+                if !cell.color_in(j, map, k, count, trace, control) {
                     // recursively call the own functions on neighbors
                     fail = true; // if it fails we break
                     break;
@@ -142,13 +476,15 @@ impl Cell {
             // if not, we continue the loop with the next item, or we return false if there are no more
         }
         // reset the current cells color (as it clearly didnt work)
-        map.0[i].color = None;
+        if map.0[i].color.take().is_some() {
+            trace.push(Step::Backtrack(i));
+        }
         false // return false since there were no correct countries
     }
-    pub fn get_avalible(&self, map: &mut Map) -> Vec<usize> {
+    pub fn get_avalible(&self, map: &mut Map, k: usize) -> Vec<usize> {
         // gets avalible colors for the country
-        // all by default
-        let mut avalible: Vec<usize> = (0..4).collect();
+        // all k by default
+        let mut avalible: Vec<usize> = (0..k).collect();
         // go through connections
         for n in self.connections.iter().map(|n| map.clone().0[*n].clone()) {
             if let Some(c) = n.color {
@@ -173,55 +509,58 @@ impl Cell {
 impl From<Map> for HashMap<String, Vec<String>> {
     fn from(map: Map) -> HashMap<String, Vec<String>> {
         // convert this map into a hashmap of cells and their neighbors
-        let mut h = HashMap::new();
-        for cell in map.0.iter() {
-            let mut v = Vec::new();
-            for c in cell.connections.iter() {
-                v.push(map.0[*c].name.clone());
-            }
-            h.insert(cell.name.clone(), v);
-        }
-        h
+        map.adjacency().into_iter().collect()
     }
 }
 
 impl TryFrom<HashMap<String, Vec<String>>> for Map {
-    type Error = &'static str; // if it fails we have a static string being the error
+    type Error = MapError; // if it fails we say which region was wrong
     fn try_from(inp: HashMap<String, Vec<String>>) -> Result<Map, Self::Error> {
+        // a hashmap has no order, so sort by name to always get the same map
+        let mut regions: Vec<(String, Vec<String>)> = inp.into_iter().collect();
+        regions.sort_by(|a, b| a.0.cmp(&b.0));
+        let map = Map::link_names(regions)?;
+        // quick check to ensure that the map is correct
+        map.check()?;
+        Ok(map)
+    }
+}
+
+impl Map {
+    pub fn adjacency(&self) -> Vec<(String, Vec<String>)> {
+        // every cell with the names of its neighbors, in map order
+        self.0
+            .iter()
+            .map(|cell| {
+                let neighbors = cell
+                    .connections
+                    .iter()
+                    .map(|c| self.0[*c].name.clone())
+                    .collect();
+                (cell.name.clone(), neighbors)
+            })
+            .collect()
+    }
+    pub fn link_names(inp: Vec<(String, Vec<String>)>) -> Result<Map, MapError> {
+        // turn a list of regions and the names of their neighbors into a map,
+        // keeping the order of the list. the links are not validated here.
         let mut map = Map::default();
-        map.add_names(inp.keys().cloned().collect());
-        let mut cons: HashMap<usize, Vec<usize>> = HashMap::new();
-        for (k, cell) in map.0.iter().enumerate() {
-            if let Some(connections) = inp.get(&cell.name) {
-                // Do some things
-                let mut v = Vec::new();
-
-                // iterate through connections
-                for connection in connections {
-                    if let Some(ind) = map.0.iter().enumerate().find_map(|(i, cell2)| {
-                        if &cell2.name == connection {
-                            Some(i)
-                        } else {
-                            None
-                        }
-                    }) {
-                        v.push(ind)
-                    } else {
-                        println!("{} {}", cell.name, connection);
-                        return Err("The input hashmap was invalid (Could not find neighbor).");
-                    }
+        map.add_names(inp.iter().map(|(name, _)| name.clone()).collect());
+        for (k, (name, connections)) in inp.iter().enumerate() {
+            let mut v = Vec::new();
+
+            // iterate through connections
+            for connection in connections {
+                if let Some(ind) = map.0.iter().position(|cell2| &cell2.name == connection) {
+                    v.push(ind)
+                } else {
+                    return Err(MapError::UnknownNeighbor {
+                        region: name.clone(),
+                        neighbor: connection.clone(),
+                    });
                 }
-                cons.insert(k, v);
-            } else {
-                return Err("The input hashmap was invalid.");
             }
-        }
-        for (i, cons) in cons {
-            map.0[i].connections = cons;
-        }
-        // quick check to ensure that the map is correct
-        if !map.validate() {
-            return Err("The input hashmap was invalid. (Validation Failed)");
+            map.0[k].connections = v;
         }
         Ok(map)
     }
@@ -0,0 +1,344 @@
+use std::{fs, path::Path, process::ExitCode, time::Duration};
+
+use map_coloring::{
+    bench,
+    cell::Map,
+    error::MapError,
+    format::{self, geojson::GeoJsonOptions, Format, LoadOptions},
+    pin,
+    solver::{control::Control, Strategy},
+};
+
+const USAGE: &str = "Usage:
+  map-coloring                          open the app
+  map-coloring color <file> [options]   color a map and print or write it
+      -k <colors>         the number of colors to use (default 4)
+      --solver <name>     dfs, welsh-powell, dsatur, local-search or engine (default engine)
+      --format <format>   toml, json or dimacs (default toml, or the extension of --output),
+                          dimacs writes the DIMACS solution format
+      --output <file>     write the colored map to a file instead of printing it
+      --repair            fix one-sided, repeated and self links instead of failing
+      --time-limit <s>    give up after this many seconds, the result is then unknown
+  map-coloring validate <file> [--repair]
+                                        list every problem with a map
+  map-coloring convert <in> <out> [--legacy] [--repair]
+                                        rewrite a map in the format of the extension of out
+                                        (toml, json, col, csv, dot or svg), --legacy for the old
+                                        region to neighbors layout (toml or json)
+  map-coloring stats <file> [--repair]  print the size and structure of a map
+  map-coloring bench                    compare the solvers on the shipped maps
+
+GeoJSON maps (.geojson) are linked where the borders of their polygons meet:
+      --name-property <p> the feature property with the region name (default name)
+      --tolerance <d>     how far apart borders can be and still count as shared (default 1e-9)
+      --point-contacts    also link regions that only touch at a single point
+
+Exit codes: 0 on success, 1 if the map is invalid or not colorable, 2 on bad usage or unreadable files,
+3 if it is unknown: the time limit ran out, or a solver that isnt exact (dfs, welsh-powell or
+local-search) found no coloring";
+
+// what went wrong, and the exit code that goes with it
+struct Failure(u8, String);
+
+fn usage(message: &str) -> Failure {
+    Failure(2, format!("{}\n\n{}", message, USAGE))
+}
+
+struct Args {
+    positional: Vec<String>,
+    k: usize,
+    solver: Strategy,
+    format: Option<Format>,
+    output: Option<String>,
+    repair: bool,
+    legacy: bool,
+    geojson: GeoJsonOptions,
+    time_limit: Option<Duration>,
+}
+
+fn parse(args: &[String]) -> Result<Args, Failure> {
+    let mut parsed = Args {
+        positional: Vec::new(),
+        k: 4,
+        solver: Strategy::Engine,
+        format: None,
+        output: None,
+        repair: false,
+        legacy: false,
+        geojson: GeoJsonOptions::default(),
+        time_limit: None,
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // options that take a value
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| usage(&format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "-k" | "--colors" => {
+                let v = value()?;
+                parsed.k = v
+                    .parse()
+                    .map_err(|_| usage(&format!("\"{}\" is not a number of colors", v)))?;
+            }
+            "--solver" => {
+                let v = value()?;
+                parsed.solver = Strategy::from_name(&v)
+                    .ok_or_else(|| usage(&format!("there is no solver called \"{}\"", v)))?;
+            }
+            "--format" => {
+                let v = value()?;
+                parsed.format = Some(
+                    Format::from_name(&v)
+                        .ok_or_else(|| usage(&format!("unknown format \"{}\"", v)))?,
+                );
+            }
+            "-o" | "--output" => parsed.output = Some(value()?),
+            "--repair" => parsed.repair = true,
+            "--legacy" => parsed.legacy = true,
+            "--name-property" => parsed.geojson.name_property = value()?,
+            "--tolerance" => {
+                let v = value()?;
+                parsed.geojson.tolerance = v
+                    .parse()
+                    .ok()
+                    .filter(|t: &f64| *t >= 0.)
+                    .ok_or_else(|| usage(&format!("\"{}\" is not a valid tolerance", v)))?;
+            }
+            "--point-contacts" => parsed.geojson.point_contacts = true,
+            "--time-limit" => {
+                let v = value()?;
+                // try_from also turns down limits too big for a Duration
+                let limit = v
+                    .parse()
+                    .ok()
+                    .and_then(|s: f64| Duration::try_from_secs_f64(s).ok())
+                    .ok_or_else(|| usage(&format!("\"{}\" is not a number of seconds", v)))?;
+                parsed.time_limit = Some(limit);
+            }
+            _ if arg.starts_with('-') => return Err(usage(&format!("unknown option {}", arg))),
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+    Ok(parsed)
+}
+
+fn load_failure(file: &str, err: MapError) -> Failure {
+    // syntax errors mean the file cant be read, problems with the links
+    // themselves come out of validation the same way for every format
+    match err {
+        MapError::Io(_) | MapError::Parse(_) | MapError::Syntax { .. } => {
+            Failure(2, format!("{}: {}", file, err))
+        }
+        _ => Failure(1, format!("{}: {}", file, err)),
+    }
+}
+
+fn load(args: &Args, count: usize) -> Result<format::Loaded, Failure> {
+    // load the first positional argument, making sure there are exactly count of them
+    if args.positional.len() != count {
+        return Err(usage(&format!(
+            "expected {} file{}",
+            count,
+            if count == 1 { "" } else { "s" }
+        )));
+    }
+    let file = &args.positional[0];
+    let options = LoadOptions {
+        repair: args.repair,
+        geojson: args.geojson.clone(),
+    };
+    let loaded =
+        format::load_with(Path::new(file), &options).map_err(|err| load_failure(file, err))?;
+    for issue in loaded.report.issues.iter() {
+        eprintln!("warning: repaired: {}", issue);
+    }
+    Ok(loaded)
+}
+
+fn write(output: Option<&String>, contents: String) -> Result<(), Failure> {
+    // write to the output file, or print if there is none
+    match output {
+        Some(file) => fs::write(file, contents).map_err(|e| Failure(2, format!("{}: {}", file, e))),
+        None => {
+            print!("{}", contents);
+            Ok(())
+        }
+    }
+}
+
+fn color(args: Args) -> Result<(), Failure> {
+    let mut loaded = load(&args, 1)?;
+    let map = &mut loaded.map;
+    map.reset_colors();
+    let control = Control::default().with_time_limit(args.time_limit);
+    let solution = args.solver.solver().solve_with(map, args.k, &control);
+    solution.apply(map);
+    eprintln!(
+        "{}: {} in {} nodes and {} backtracks ({:.2} ms)",
+        args.solver.name(),
+        if solution.report.colored {
+            "colored"
+        } else if solution.report.stopped {
+            "unknown, out of time"
+        } else if solution.report.gave_up {
+            "unknown, no coloring found"
+        } else {
+            "not colorable"
+        },
+        solution.report.iterations,
+        solution.report.backtracks,
+        solution.elapsed.as_secs_f64() * 1000.
+    );
+    loaded.header.k = Some(args.k);
+    loaded.header.solver = Some(args.solver.name().to_string());
+    let format = args
+        .format
+        .or_else(|| {
+            args.output
+                .as_ref()
+                .and_then(|o| Format::from_path(Path::new(o)))
+        })
+        .unwrap_or(Format::Toml);
+    let contents = if format == Format::Dimacs {
+        // a colored DIMACS graph is written as a solution file
+        Ok(format::dimacs::write_solution(map, args.k))
+    } else {
+        format::to_string(map, loaded.header, format)
+    }
+    .map_err(|e| Failure(2, e.to_string()))?;
+    write(args.output.as_ref(), contents)?;
+    if solution.report.colored {
+        Ok(())
+    } else if solution.report.stopped {
+        Err(Failure(
+            3,
+            format!(
+                "gave up after the time limit, it is unknown if the map can be colored with {} colors",
+                args.k
+            ),
+        ))
+    } else if solution.report.gave_up {
+        Err(Failure(
+            3,
+            format!(
+                "{} found no coloring with {} colors, but it isnt exact so there may still be one",
+                args.solver.name(),
+                args.k
+            ),
+        ))
+    } else {
+        // say so if the pins are why it failed
+        let control = Control::default().with_time_limit(Some(Duration::from_secs(5)));
+        let reason = pin::blame(map, args.k, &control)
+            .map_or(String::new(), |reason| format!(": {}", reason));
+        Err(Failure(
+            1,
+            format!(
+                "the map can not be colored with {} colors{}",
+                args.k, reason
+            ),
+        ))
+    }
+}
+
+fn validate(args: Args) -> Result<(), Failure> {
+    if args.positional.len() != 1 {
+        return Err(usage("expected 1 file"));
+    }
+    let file = &args.positional[0];
+    if args.repair {
+        // anything that could be repaired is printed as a warning by load
+        load(&args, 1)?;
+        println!("{}: valid after repairs", file);
+        return Ok(());
+    }
+    // load with repairs on, so every problem can be listed instead of just the first
+    let options = LoadOptions {
+        repair: true,
+        geojson: args.geojson.clone(),
+    };
+    let loaded =
+        format::load_with(Path::new(file), &options).map_err(|err| load_failure(file, err))?;
+    if loaded.report.is_valid() {
+        println!("{}: valid", file);
+        return Ok(());
+    }
+    for issue in loaded.report.issues.iter() {
+        println!("{}", issue);
+    }
+    Err(Failure(
+        1,
+        format!("{}: {} problems", file, loaded.report.issues.len()),
+    ))
+}
+
+fn convert(args: Args) -> Result<(), Failure> {
+    let loaded = load(&args, 2)?;
+    let output = Path::new(&args.positional[1]);
+    let format = args
+        .format
+        .or_else(|| Format::from_path(output))
+        .unwrap_or(Format::Toml);
+    let contents = if args.legacy {
+        format::to_legacy_string(&loaded.map, format)
+    } else {
+        format::to_string(&loaded.map, loaded.header, format)
+    }
+    .map_err(|e| Failure(2, e.to_string()))?;
+    write(Some(&args.positional[1]), contents)
+}
+
+fn stats(args: Args) -> Result<(), Failure> {
+    let loaded = load(&args, 1)?;
+    let map: &Map = &loaded.map;
+    let degrees: Vec<usize> = map.0.iter().map(|c| c.connections.len()).collect();
+    let links = degrees.iter().sum::<usize>() / 2;
+    println!("name:       {}", loaded.header.name);
+    println!("regions:    {}", map.0.len());
+    println!("links:      {}", links);
+    println!("components: {}", map.components().len());
+    if !map.0.is_empty() {
+        println!(
+            "degree:     min {}, max {}, average {:.2}",
+            degrees.iter().min().unwrap(),
+            degrees.iter().max().unwrap(),
+            2. * links as f64 / map.0.len() as f64
+        );
+    }
+    let clique: Vec<String> = map
+        .max_clique()
+        .iter()
+        .map(|c| map.0[*c].name.clone())
+        .collect();
+    println!("clique:     {} ({})", clique.len(), clique.join(", "));
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> ExitCode {
+    // run a command given on the command line (without the program name)
+    let res = match args[0].as_str() {
+        "color" => parse(&args[1..]).and_then(color),
+        "validate" => parse(&args[1..]).and_then(validate),
+        "convert" => parse(&args[1..]).and_then(convert),
+        "stats" => parse(&args[1..]).and_then(stats),
+        "bench" => {
+            bench::run();
+            Ok(())
+        }
+        "help" | "-h" | "--help" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        command => Err(usage(&format!("unknown command \"{}\"", command))),
+    };
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(Failure(code, message)) => {
+            eprintln!("{}", message);
+            ExitCode::from(code)
+        }
+    }
+}
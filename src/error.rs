@@ -0,0 +1,84 @@
+use std::{fmt, io};
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(io::Error), // the file could not be read
+    Parse(String), // the file is not valid TOML / not a map
+    // a line based file has a mistake
+    Syntax {
+        line: usize,
+        column: Option<usize>,
+        message: String,
+    },
+    // a region lists a neighbor that doesnt exist
+    UnknownNeighbor {
+        region: String,
+        neighbor: String,
+    },
+    // from lists to, but to doesnt list from
+    AsymmetricLink {
+        from: String,
+        to: String,
+    },
+    SelfLoop(String), // a region lists itself as a neighbor
+    // from lists to more than once
+    DuplicateLink {
+        from: String,
+        to: String,
+    },
+    DuplicateRegion(String), // two regions share the same name
+    Unsupported(String),     // the map cant be written the way it was asked for
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Io(err) => write!(f, "Could not read the file: {}", err),
+            MapError::Parse(err) => write!(f, "Could not parse the file: {}", err),
+            MapError::Syntax {
+                line,
+                column: Some(column),
+                message,
+            } => write!(f, "Line {}, column {}: {}", line, column, message),
+            MapError::Syntax { line, message, .. } => write!(f, "Line {}: {}", line, message),
+            MapError::UnknownNeighbor { region, neighbor } => write!(
+                f,
+                "\"{}\" lists \"{}\" as a neighbor, but there is no region called \"{}\"",
+                region, neighbor, neighbor
+            ),
+            MapError::AsymmetricLink { from, to } => write!(
+                f,
+                "\"{}\" lists \"{}\" as a neighbor, but \"{}\" does not list \"{}\"",
+                from, to, to, from
+            ),
+            MapError::SelfLoop(name) => write!(f, "\"{}\" lists itself as a neighbor", name),
+            MapError::DuplicateLink { from, to } => {
+                write!(f, "\"{}\" lists \"{}\" more than once", from, to)
+            }
+            MapError::DuplicateRegion(name) => {
+                write!(f, "There is more than one region called \"{}\"", name)
+            }
+            MapError::Unsupported(err) => write!(f, "Could not write the map: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(err: io::Error) -> MapError {
+        MapError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for MapError {
+    fn from(err: toml::de::Error) -> MapError {
+        MapError::Parse(err.to_string())
+    }
+}
+
+impl From<serde_json::Error> for MapError {
+    fn from(err: serde_json::Error) -> MapError {
+        MapError::Parse(err.to_string())
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{cell::Map, error::MapError};
+
+fn syntax(line: usize, column: usize, message: String) -> MapError {
+    MapError::Syntax {
+        line,
+        column: Some(column),
+        message,
+    }
+}
+
+fn split(line: &str, line_number: usize) -> Result<Vec<String>, MapError> {
+    // split a CSV line into its fields, fields can be quoted to hold commas
+    // and "" inside quotes is a single quote
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().unwrap();
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(String::new()),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(syntax(
+            line_number,
+            fields.len(),
+            "the quote is never closed".to_string(),
+        ));
+    }
+    Ok(fields.into_iter().map(|f| f.trim().to_string()).collect())
+}
+
+fn rows(s: &str) -> Result<Vec<(usize, Vec<String>)>, MapError> {
+    // every non empty line with its line number
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Ok((i + 1, split(line.trim_end_matches('\r'), i + 1)?)))
+        .collect()
+}
+
+fn add(regions: &mut Vec<(String, Vec<String>)>, name: &str) -> usize {
+    // the position of the region, adding it if it is new
+    match regions.iter().position(|(n, _)| n == name) {
+        Some(i) => i,
+        None => {
+            regions.push((name.to_string(), Vec::new()));
+            regions.len() - 1
+        }
+    }
+}
+
+pub fn read_edge_list(s: &str) -> Result<Map, MapError> {
+    // read one link per line as `region_a,region_b`. a header row with those
+    // names is skipped, and a line with only one region adds it without links
+    let mut regions: Vec<(String, Vec<String>)> = Vec::new();
+    for (n, (line, fields)) in rows(s)?.into_iter().enumerate() {
+        if n == 0
+            && fields.len() == 2
+            && fields[0].eq_ignore_ascii_case("region_a")
+            && fields[1].eq_ignore_ascii_case("region_b")
+        {
+            continue;
+        }
+        if fields.len() > 2 {
+            return Err(syntax(
+                line,
+                3,
+                format!("expected 2 columns but there are {}", fields.len()),
+            ));
+        }
+        let a = &fields[0];
+        let b = fields.get(1).cloned().unwrap_or_default();
+        if a.is_empty() {
+            return Err(syntax(line, 1, "the region name is empty".to_string()));
+        }
+        let i = add(&mut regions, a);
+        if b.is_empty() {
+            continue;
+        }
+        let j = add(&mut regions, &b);
+        // links that are listed twice (in either direction) are only added once.
+        // a region linked to itself is left for validation to report
+        if !regions[i].1.contains(&b) {
+            regions[i].1.push(b.clone());
+            if i != j {
+                regions[j].1.push(a.clone());
+            }
+        }
+    }
+    Map::link_names(regions)
+}
+
+pub fn read_matrix(s: &str) -> Result<Map, MapError> {
+    // read a square adjacency matrix: the header row names the regions after a
+    // corner cell (empty or a label), every row starts with the same name as its
+    // column and a 1 links two regions. links that only go one way are left for
+    // validation, like in the other formats
+    let rows = rows(s)?;
+    let (header_line, header) = rows
+        .first()
+        .ok_or_else(|| MapError::Parse("the file is empty".to_string()))?;
+    let names: Vec<String> = header[1..].to_vec();
+    if let Some(i) = names.iter().position(|n| n.is_empty()) {
+        return Err(syntax(
+            *header_line,
+            i + 2,
+            "the region name is empty".to_string(),
+        ));
+    }
+    if rows.len() - 1 != names.len() {
+        return Err(MapError::Parse(format!(
+            "the header names {} regions but there are {} rows",
+            names.len(),
+            rows.len() - 1
+        )));
+    }
+    let mut linked = vec![vec![false; names.len()]; names.len()];
+    for (i, (line, fields)) in rows[1..].iter().enumerate() {
+        if fields.len() != names.len() + 1 {
+            return Err(syntax(
+                *line,
+                fields.len().min(names.len() + 1),
+                format!(
+                    "expected {} columns but there are {}",
+                    names.len() + 1,
+                    fields.len()
+                ),
+            ));
+        }
+        if fields[0] != names[i] {
+            return Err(syntax(
+                *line,
+                1,
+                format!(
+                    "expected the row of \"{}\" but found \"{}\"",
+                    names[i], fields[0]
+                ),
+            ));
+        }
+        for (j, value) in fields[1..].iter().enumerate() {
+            linked[i][j] = match value.as_str() {
+                "1" => true,
+                "0" | "" => false,
+                _ => {
+                    return Err(syntax(
+                        *line,
+                        j + 2,
+                        format!("expected 0 or 1 but found \"{}\"", value),
+                    ))
+                }
+            };
+        }
+    }
+    let regions = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let neighbors = (0..names.len())
+                .filter(|j| linked[i][*j])
+                .map(|j| names[j].clone())
+                .collect();
+            (name.clone(), neighbors)
+        })
+        .collect();
+    Map::link_names(regions)
+}
+
+fn is_matrix(rows: &[(usize, Vec<String>)]) -> bool {
+    // a matrix starts with an empty cell (above the row names), or has a row
+    // for every region in the header with the same names in the same order
+    // and nothing but 0, 1 or empty cells after them. an edge list like
+    // "A,B" then "B,C" has matching names too, but C isnt a link
+    let header = match rows.first() {
+        Some((_, header)) => header,
+        None => return false,
+    };
+    header[0].is_empty()
+        || (header.len() == rows.len()
+            && header[1..]
+                .iter()
+                .zip(rows[1..].iter())
+                .all(|(name, (_, row))| *name == row[0])
+            && rows[1..]
+                .iter()
+                .flat_map(|(_, row)| row[1..].iter())
+                .all(|value| matches!(value.as_str(), "0" | "1" | "")))
+}
+
+pub fn read(s: &str) -> Result<Map, MapError> {
+    if is_matrix(&rows(s)?) {
+        read_matrix(s)
+    } else {
+        read_edge_list(s)
+    }
+}
+
+fn quote(s: &str) -> String {
+    // quote a field if it needs it
+    if s.contains([',', '"']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+pub fn write_edge_list(map: &Map) -> String {
+    // write every link once, and regions without links on their own
+    let mut s = "region_a,region_b\n".to_string();
+    for (i, cell) in map.0.iter().enumerate() {
+        if cell.connections.is_empty() {
+            s += &format!("{},\n", quote(&cell.name));
+        }
+        for j in cell.connections.iter().filter(|j| **j > i) {
+            s += &format!("{},{}\n", quote(&cell.name), quote(&map.0[*j].name));
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(s: &str) -> (usize, usize, String) {
+        // the line, column and message of the error reading s gives
+        match read(s) {
+            Err(MapError::Syntax {
+                line,
+                column: Some(column),
+                message,
+            }) => (line, column, message),
+            other => panic!("expected a syntax error, got {:?}", other.map(|m| m.0)),
+        }
+    }
+
+    fn names(map: &Map, i: usize) -> Vec<&str> {
+        map.0[i]
+            .connections
+            .iter()
+            .map(|j| map.0[*j].name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn reads_edge_lists() {
+        let map = read("region_a,region_b\nA,B\n\"C, D\",A\nB,A\nE,\n").unwrap();
+        let regions: Vec<&str> = map.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(regions, vec!["A", "B", "C, D", "E"]);
+        assert_eq!(names(&map, 0), vec!["B", "C, D"]);
+        assert!(map.0[3].connections.is_empty());
+    }
+
+    #[test]
+    fn reads_matrices_with_or_without_a_corner_label() {
+        for corner in ["", "region"] {
+            let s = format!("{},A,B,C\nA,0,1,0\nB,1,0,1\nC,0,1,0\n", corner);
+            let map = read(&s).unwrap();
+            assert_eq!(map.0.len(), 3);
+            assert_eq!(names(&map, 1), vec!["A", "C"]);
+        }
+    }
+
+    #[test]
+    fn edge_lists_with_matching_names_arent_matrices() {
+        let map = read("A,B\nB,C\n").unwrap();
+        let regions: Vec<&str> = map.0.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(regions, vec!["A", "B", "C"]);
+        assert_eq!(names(&map, 1), vec!["A", "C"]);
+    }
+
+    #[test]
+    fn asymmetric_matrices_are_left_for_validation() {
+        let map = read("region,A,B\nA,0,1\nB,0,0\n").unwrap();
+        assert!(!map.validation_report().is_valid());
+    }
+
+    #[test]
+    fn errors_point_at_the_line_and_column() {
+        assert_eq!(
+            error("A,B\nA,B,C\n"),
+            (2, 3, "expected 2 columns but there are 3".to_string())
+        );
+        assert_eq!(
+            error("A,B\n,B\n"),
+            (2, 1, "the region name is empty".to_string())
+        );
+        assert_eq!(
+            error("A,\"B\n"),
+            (1, 2, "the quote is never closed".to_string())
+        );
+        assert_eq!(
+            error(",A,B\nA,0,1\nB,1,0,1\n"),
+            (3, 3, "expected 3 columns but there are 4".to_string())
+        );
+        assert_eq!(
+            error(",A,B\nB,0,1\nA,1,0\n"),
+            (
+                2,
+                1,
+                "expected the row of \"A\" but found \"B\"".to_string()
+            )
+        );
+        assert_eq!(
+            error(",A,B\nA,0,x\nB,1,0\n"),
+            (2, 3, "expected 0 or 1 but found \"x\"".to_string())
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{cell::Map, error::MapError};
+
+fn syntax(line: usize, message: String) -> MapError {
+    MapError::Syntax {
+        line,
+        column: None,
+        message,
+    }
+}
+
+fn vertex(field: Option<&str>, n: usize, line: usize) -> Result<usize, MapError> {
+    // parse a 1 based vertex number into a cell index
+    let field = field.ok_or_else(|| syntax(line, "missing vertex".to_string()))?;
+    match field.parse::<usize>() {
+        Ok(v) if v >= 1 && v <= n => Ok(v - 1),
+        _ => Err(syntax(
+            line,
+            format!("\"{}\" is not a vertex between 1 and {}", field, n),
+        )),
+    }
+}
+
+pub fn read(s: &str) -> Result<Map, MapError> {
+    // read a DIMACS graph (`p edge N M` followed by `e u v` lines).
+    // the regions are called v1 to vN, and edges listed twice are only linked once
+    let mut map: Option<Map> = None;
+    for (i, line) in s.lines().enumerate() {
+        let line_number = i + 1;
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            None | Some("c") => {} // blank lines and comments
+            Some("p") => {
+                if map.is_some() {
+                    return Err(syntax(
+                        line_number,
+                        "more than one problem line".to_string(),
+                    ));
+                }
+                // the format is usually "edge", but "col" is seen in the wild too
+                let n = match (fields.next(), fields.next()) {
+                    (Some("edge" | "col"), Some(n)) => n.parse::<usize>().map_err(|_| {
+                        syntax(line_number, format!("\"{}\" is not a vertex count", n))
+                    })?,
+                    _ => {
+                        return Err(syntax(
+                            line_number,
+                            "expected a problem line like \"p edge 10 20\"".to_string(),
+                        ))
+                    }
+                };
+                let mut new = Map::default();
+                new.add_names((1..=n).map(|v| format!("v{}", v)).collect());
+                map = Some(new);
+            }
+            Some("e") => {
+                let map = map.as_mut().ok_or_else(|| {
+                    syntax(line_number, "edge before the problem line".to_string())
+                })?;
+                let n = map.0.len();
+                let u = vertex(fields.next(), n, line_number)?;
+                let v = vertex(fields.next(), n, line_number)?;
+                // a vertex linked to itself is left for validation to report
+                if !map.0[u].connections.contains(&v) {
+                    map.0[u].connections.push(v);
+                    if u != v {
+                        map.0[v].connections.push(u);
+                    }
+                }
+            }
+            Some(other) => {
+                return Err(syntax(
+                    line_number,
+                    format!("unknown line type \"{}\"", other),
+                ))
+            }
+        }
+    }
+    map.ok_or_else(|| MapError::Parse("there is no problem line (\"p edge N M\")".to_string()))
+}
+
+pub fn write(map: &Map, name: &str) -> String {
+    // write the map as a DIMACS graph, with the region names as comments
+    let edges: Vec<(usize, usize)> = map
+        .0
+        .iter()
+        .enumerate()
+        .flat_map(|(i, cell)| {
+            cell.connections
+                .iter()
+                .filter(move |j| **j > i)
+                .map(move |j| (i, *j))
+        })
+        .collect();
+    let mut s = String::new();
+    if !name.is_empty() {
+        s += &format!("c {}\n", name);
+    }
+    for (i, cell) in map.0.iter().enumerate() {
+        s += &format!("c v{} {}\n", i + 1, cell.name);
+    }
+    s += &format!("p edge {} {}\n", map.0.len(), edges.len());
+    for (i, j) in edges {
+        s += &format!("e {} {}\n", i + 1, j + 1);
+    }
+    s
+}
+
+pub fn write_solution(map: &Map, k: usize) -> String {
+    // write the colors in the DIMACS solution format: `s col K` with the number
+    // of colors used, then `l v c` for every colored vertex (colors start at 1)
+    let used = map
+        .0
+        .iter()
+        .filter_map(|c| c.color)
+        .max()
+        .map_or(0, |c| c + 1);
+    let mut s = format!("c colored with at most {} colors\ns col {}\n", k, used);
+    for (i, cell) in map.0.iter().enumerate() {
+        if let Some(c) = cell.color {
+            s += &format!("l {} {}\n", i + 1, c + 1);
+        }
+    }
+    s
+}
+
+pub fn read_solution(s: &str, map: &mut Map) -> Result<(), MapError> {
+    // read a DIMACS solution and color the map with it
+    let n = map.0.len();
+    for (i, line) in s.lines().enumerate() {
+        let line_number = i + 1;
+        let mut fields = line.split_whitespace();
+        // only the l lines matter, the others describe the solution
+        if fields.next() == Some("l") {
+            let v = vertex(fields.next(), n, line_number)?;
+            let c = fields.next().and_then(|c| c.parse::<usize>().ok());
+            match c {
+                Some(c) if c >= 1 => map.0[v].color = Some(c - 1),
+                _ => {
+                    return Err(syntax(
+                        line_number,
+                        "expected a color from 1 up".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(s: &str) -> (usize, String) {
+        // the line and message of the error reading s gives
+        match read(s) {
+            Err(MapError::Syntax {
+                line,
+                column: None,
+                message,
+            }) => (line, message),
+            other => panic!("expected a syntax error, got {:?}", other.map(|m| m.0)),
+        }
+    }
+
+    #[test]
+    fn reads_edges_once() {
+        let map = read("c a triangle\np edge 3 4\ne 1 2\ne 2 3\ne 3 1\ne 2 1\n").unwrap();
+        assert_eq!(map.0.len(), 3);
+        assert_eq!(map.0[0].name, "v1");
+        assert_eq!(map.0[0].connections, vec![1, 2]);
+        assert_eq!(map.0[1].connections, vec![0, 2]);
+        let looped = read("p edge 2 1\ne 2 2\n").unwrap();
+        assert_eq!(looped.0[1].connections, vec![1]);
+    }
+
+    #[test]
+    fn errors_point_at_the_line() {
+        assert_eq!(
+            error("p edge 3 1\n\ne 1 4\n"),
+            (3, "\"4\" is not a vertex between 1 and 3".to_string())
+        );
+        assert_eq!(
+            error("c hi\ne 1 2\n"),
+            (2, "edge before the problem line".to_string())
+        );
+        assert_eq!(
+            error("p edge 2 1\np edge 2 1\n"),
+            (2, "more than one problem line".to_string())
+        );
+        assert_eq!(
+            error("p edge x 1\n"),
+            (1, "\"x\" is not a vertex count".to_string())
+        );
+        assert_eq!(
+            error("p edge 2 1\nx 1 2\n"),
+            (2, "unknown line type \"x\"".to_string())
+        );
+        assert!(matches!(read("c nothing\n"), Err(MapError::Parse(_))));
+    }
+
+    #[test]
+    fn solutions_round_trip() {
+        let mut map = read("p edge 3 2\ne 1 2\ne 2 3\n").unwrap();
+        map.0[0].color = Some(0);
+        map.0[1].color = Some(1);
+        map.0[2].color = Some(0);
+        let mut copy = read("p edge 3 2\ne 1 2\ne 2 3\n").unwrap();
+        read_solution(&write_solution(&map, 2), &mut copy).unwrap();
+        let colors: Vec<Option<usize>> = copy.0.iter().map(|c| c.color).collect();
+        assert_eq!(colors, vec![Some(0), Some(1), Some(0)]);
+        assert!(matches!(
+            read_solution("s col 2\nl 1 0\n", &mut copy),
+            Err(MapError::Syntax { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,35 @@
+use crate::cell::Map;
+
+fn quote(s: &str) -> String {
+    // a DOT string, with quotes and backslashes escaped
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub fn write(map: &Map, name: &str) -> String {
+    // write the map as an undirected Graphviz graph, one filled node per cell
+    // in the color of the cell and one edge per link
+    let mut s = format!("graph {} {{\n", quote(name));
+    s += "    node [shape=circle, style=filled];\n";
+    for cell in map.0.iter() {
+        let color = cell.color();
+        let mut attributes = vec![
+            format!("fillcolor={}", quote(&color.hex())),
+            format!("fontcolor={}", quote(&color.contrast().hex())),
+        ];
+        if let Some(label) = &cell.label {
+            attributes.push(format!("label={}", quote(label)));
+        }
+        if let Some(description) = &cell.description {
+            attributes.push(format!("tooltip={}", quote(description)));
+        }
+        s += &format!("    {} [{}];\n", quote(&cell.name), attributes.join(", "));
+    }
+    for (i, cell) in map.0.iter().enumerate() {
+        // every link is listed by both cells, only write it once
+        for j in cell.connections.iter().filter(|j| **j > i) {
+            s += &format!("    {} -- {};\n", quote(&cell.name), quote(&map.0[*j].name));
+        }
+    }
+    s += "}\n";
+    s
+}
@@ -0,0 +1,286 @@
+use serde_json::Value;
+
+use crate::{cell::Map, error::MapError};
+
+#[derive(Clone, Debug)]
+pub struct GeoJsonOptions {
+    pub name_property: String, // the feature property that holds the name of the region
+    pub tolerance: f64,        // how far apart two boundaries can be and still count as shared
+    pub point_contacts: bool,  // whether regions that only touch at a point are neighbors
+}
+
+impl Default for GeoJsonOptions {
+    fn default() -> GeoJsonOptions {
+        GeoJsonOptions {
+            name_property: "name".to_string(),
+            tolerance: 1e-9,
+            point_contacts: false,
+        }
+    }
+}
+
+type Point = (f64, f64);
+type Segment = (Point, Point);
+
+struct Region {
+    name: String,
+    segments: Vec<Segment>, // every edge of every ring of the polygons
+    bounds: (Point, Point), // the smallest and largest x and y
+}
+
+fn point(value: &Value) -> Option<Point> {
+    let coordinates = value.as_array()?;
+    Some((
+        coordinates.first()?.as_f64()?,
+        coordinates.get(1)?.as_f64()?,
+    ))
+}
+
+fn add_polygon(polygon: &Value, segments: &mut Vec<Segment>) -> Option<()> {
+    // a polygon is a list of rings, and a ring a list of points
+    for ring in polygon.as_array()? {
+        let points = ring
+            .as_array()?
+            .iter()
+            .map(point)
+            .collect::<Option<Vec<Point>>>()?;
+        for pair in points.windows(2) {
+            segments.push((pair[0], pair[1]));
+        }
+    }
+    Some(())
+}
+
+fn region(feature: &Value, i: usize, options: &GeoJsonOptions) -> Result<Region, MapError> {
+    let invalid = |message: &str| MapError::Parse(format!("feature {}: {}", i + 1, message));
+    let name = match &feature["properties"][&options.name_property] {
+        Value::String(s) => s.clone(),
+        Value::Number(n) => n.to_string(),
+        _ => {
+            return Err(invalid(&format!(
+                "there is no \"{}\" property to name the region with",
+                options.name_property
+            )))
+        }
+    };
+    let geometry = &feature["geometry"];
+    let coordinates = &geometry["coordinates"];
+    let mut segments = Vec::new();
+    let ok = match geometry["type"].as_str() {
+        Some("Polygon") => add_polygon(coordinates, &mut segments),
+        Some("MultiPolygon") => coordinates.as_array().and_then(|polygons| {
+            polygons
+                .iter()
+                .try_for_each(|p| add_polygon(p, &mut segments))
+        }),
+        _ => {
+            return Err(invalid(
+                "only Polygon and MultiPolygon geometries are supported",
+            ))
+        }
+    };
+    if ok.is_none() || segments.is_empty() {
+        return Err(invalid(&format!("the polygon of \"{}\" is invalid", name)));
+    }
+    let mut bounds = (segments[0].0, segments[0].0);
+    for (a, b) in segments.iter() {
+        for p in [a, b] {
+            bounds.0 = (bounds.0 .0.min(p.0), bounds.0 .1.min(p.1));
+            bounds.1 = (bounds.1 .0.max(p.0), bounds.1 .1.max(p.1));
+        }
+    }
+    Ok(Region {
+        name,
+        segments,
+        bounds,
+    })
+}
+
+fn sub(a: Point, b: Point) -> Point {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+fn cross(a: Point, b: Point) -> f64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+fn dot(a: Point, b: Point) -> f64 {
+    a.0 * b.0 + a.1 * b.1
+}
+
+fn shared_length(a: Segment, b: Segment, tolerance: f64) -> f64 {
+    // how long the stretch is that a and b run along each other (0 if they dont)
+    let d = sub(a.1, a.0);
+    let length = dot(d, d).sqrt();
+    if length <= tolerance {
+        return 0.;
+    }
+    let (p, q) = (sub(b.0, a.0), sub(b.1, a.0));
+    // both ends of b have to lie on the line through a
+    if cross(d, p).abs() / length > tolerance || cross(d, q).abs() / length > tolerance {
+        return 0.;
+    }
+    let (tp, tq) = (dot(d, p) / length, dot(d, q) / length);
+    (length.min(tp.max(tq)) - tp.min(tq).max(0.)).max(0.)
+}
+
+fn distance(p: Point, s: Segment) -> f64 {
+    // the distance from p to the closest point on s
+    let d = sub(s.1, s.0);
+    let length = dot(d, d);
+    let t = if length == 0. {
+        0.
+    } else {
+        (dot(sub(p, s.0), d) / length).clamp(0., 1.)
+    };
+    let closest = (s.0 .0 + t * d.0, s.0 .1 + t * d.1);
+    let offset = sub(p, closest);
+    dot(offset, offset).sqrt()
+}
+
+fn adjacent(a: &Region, b: &Region, options: &GeoJsonOptions) -> bool {
+    let tolerance = options.tolerance;
+    // regions whose bounds dont even touch cant share anything
+    if a.bounds.1 .0 + tolerance < b.bounds.0 .0
+        || b.bounds.1 .0 + tolerance < a.bounds.0 .0
+        || a.bounds.1 .1 + tolerance < b.bounds.0 .1
+        || b.bounds.1 .1 + tolerance < a.bounds.0 .1
+    {
+        return false;
+    }
+    let mut touching = false;
+    for sa in a.segments.iter() {
+        for sb in b.segments.iter() {
+            if shared_length(*sa, *sb, tolerance) > tolerance {
+                return true; // a real shared border
+            }
+            touching = touching
+                || [sb.0, sb.1].iter().any(|p| distance(*p, *sa) <= tolerance)
+                || [sa.0, sa.1].iter().any(|p| distance(*p, *sb) <= tolerance);
+        }
+    }
+    // only touching at a point, like the four corners
+    touching && options.point_contacts
+}
+
+pub fn read(s: &str, options: &GeoJsonOptions) -> Result<Map, MapError> {
+    // read a FeatureCollection of polygons and link every two regions that
+    // share a stretch of border
+    let value: Value = serde_json::from_str(s)?;
+    let features = match (value["type"].as_str(), value["features"].as_array()) {
+        (Some("FeatureCollection"), Some(features)) => features,
+        _ => {
+            return Err(MapError::Parse(
+                "expected a GeoJSON FeatureCollection".to_string(),
+            ))
+        }
+    };
+    let regions = features
+        .iter()
+        .enumerate()
+        .map(|(i, f)| region(f, i, options))
+        .collect::<Result<Vec<Region>, MapError>>()?;
+    let mut map = Map::default();
+    map.add_names(regions.iter().map(|r| r.name.clone()).collect());
+    for i in 0..regions.len() {
+        for j in i + 1..regions.len() {
+            if adjacent(&regions[i], &regions[j], options) {
+                map.0[i].connections.push(j);
+                map.0[j].connections.push(i);
+            }
+        }
+    }
+    Ok(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(name: &str, x: f64, y: f64) -> String {
+        // a unit square feature with its lower left corner at x, y
+        format!(
+            r#"{{"type": "Feature", "properties": {{"name": "{}"}}, "geometry": {{"type": "Polygon",
+            "coordinates": [[[{x}, {y}], [{x1}, {y}], [{x1}, {y1}], [{x}, {y1}], [{x}, {y}]]]}}}}"#,
+            name,
+            x = x,
+            y = y,
+            x1 = x + 1.,
+            y1 = y + 1.
+        )
+    }
+
+    fn grid() -> String {
+        // four squares in a 2x2 grid: A B on the bottom, C D on top
+        let features = [
+            square("A", 0., 0.),
+            square("B", 1., 0.),
+            square("C", 0., 1.),
+            square("D", 1., 1.),
+        ];
+        format!(
+            r#"{{"type": "FeatureCollection", "features": [{}]}}"#,
+            features.join(",")
+        )
+    }
+
+    fn neighbors(map: &Map, i: usize) -> Vec<&str> {
+        let mut names: Vec<&str> = map.0[i]
+            .connections
+            .iter()
+            .map(|j| map.0[*j].name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn shared_edges_link_regions() {
+        let map = read(&grid(), &GeoJsonOptions::default()).unwrap();
+        // A and D only meet at the middle corner
+        assert_eq!(neighbors(&map, 0), vec!["B", "C"]);
+        assert_eq!(neighbors(&map, 3), vec!["B", "C"]);
+    }
+
+    #[test]
+    fn point_contacts_only_when_asked() {
+        let options = GeoJsonOptions {
+            point_contacts: true,
+            ..Default::default()
+        };
+        let map = read(&grid(), &options).unwrap();
+        assert_eq!(neighbors(&map, 0), vec!["B", "C", "D"]);
+        assert_eq!(neighbors(&map, 1), vec!["A", "C", "D"]);
+    }
+
+    #[test]
+    fn partly_shared_edges_and_gaps() {
+        // B is shifted up by half, so it still shares half an edge with A,
+        // and C is a little apart from A
+        let s = format!(
+            r#"{{"type": "FeatureCollection", "features": [{}, {}, {}]}}"#,
+            square("A", 0., 0.),
+            square("B", 1., 0.5),
+            square("C", -1.01, 0.)
+        );
+        let map = read(&s, &GeoJsonOptions::default()).unwrap();
+        assert_eq!(neighbors(&map, 0), vec!["B"]);
+        let options = GeoJsonOptions {
+            tolerance: 0.02,
+            ..Default::default()
+        };
+        let map = read(&s, &options).unwrap();
+        assert_eq!(neighbors(&map, 0), vec!["B", "C"]);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let options = GeoJsonOptions::default();
+        assert!(matches!(
+            read(r#"{"type": "Feature"}"#, &options),
+            Err(MapError::Parse(_))
+        ));
+        let unnamed = grid().replace("\"name\"", "\"title\"");
+        assert!(matches!(read(&unnamed, &options), Err(MapError::Parse(_))));
+    }
+}
@@ -0,0 +1,112 @@
+use serde::Serialize;
+use serde_json::{Map as Object, Value};
+
+use crate::{cell::Map, error::MapError};
+
+use super::schema::{Header, MapFile};
+
+pub fn read(s: &str) -> Result<(Map, Header), MapError> {
+    // read any of the three JSON layouts:
+    // the versioned schema ({"header": ..., "regions": [...]}),
+    // the cells of a map ([{"name": ..., "connections": [...], "color": ...}]),
+    // or the legacy adjacency ({"region": ["neighbor", ...]})
+    let value: Value = serde_json::from_str(s)?;
+    match value {
+        Value::Object(object) if object.get("header").is_some_and(|h| h.is_object()) => {
+            serde_json::from_value::<MapFile>(Value::Object(object))?.into_map()
+        }
+        Value::Array(_) => Ok((read_cells(value)?, Header::default())),
+        Value::Object(object) => {
+            let mut regions = Vec::new();
+            // serde_json keeps the order of the file, so the map is the same every time
+            for (name, neighbors) in object {
+                let neighbors: Vec<String> = serde_json::from_value(neighbors).map_err(|e| {
+                    MapError::Parse(format!("the neighbors of \"{}\" are invalid: {}", name, e))
+                })?;
+                regions.push((name, neighbors));
+            }
+            Ok((Map::link_names(regions)?, Header::default()))
+        }
+        _ => Err(MapError::Parse(
+            "expected an object of regions or an array of cells".to_string(),
+        )),
+    }
+}
+
+fn read_cells(value: Value) -> Result<Map, MapError> {
+    // the cells link to each other by index, so make sure every index exists
+    let map: Map = serde_json::from_value(value)?;
+    for cell in map.0.iter() {
+        if let Some(c) = cell.connections.iter().find(|c| **c >= map.0.len()) {
+            return Err(MapError::UnknownNeighbor {
+                region: cell.name.clone(),
+                neighbor: format!("#{}", c),
+            });
+        }
+    }
+    Ok(map)
+}
+
+fn pretty<T: Serialize>(value: &T) -> Result<String, MapError> {
+    // anything that goes wrong here is a problem writing, not reading
+    serde_json::to_string_pretty(value).map_err(|e| MapError::Unsupported(e.to_string()))
+}
+
+pub fn write(file: &MapFile) -> Result<String, MapError> {
+    // write the versioned schema
+    pretty(file)
+}
+
+pub fn write_legacy(map: &Map) -> Result<String, MapError> {
+    // write the legacy adjacency, region names to neighbor names, in map order
+    let mut object = Object::new();
+    for (name, neighbors) in map.adjacency() {
+        object.insert(name, neighbors.into());
+    }
+    pretty(&object)
+}
+
+pub fn write_cells(map: &Map) -> Result<String, MapError> {
+    // write the cells of the map as they are, linked by index
+    pretty(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::graph;
+
+    fn path() -> Map {
+        // three regions in a row, with colors
+        let mut map = graph(3, &[(0, 1), (1, 2)]);
+        for (i, cell) in map.0.iter_mut().enumerate() {
+            cell.color = Some(i % 2);
+        }
+        map
+    }
+
+    fn colors(map: &Map) -> Vec<Option<usize>> {
+        map.0.iter().map(|c| c.color).collect()
+    }
+
+    #[test]
+    fn every_layout_reads_back() {
+        let map = path();
+        let (schema, _) = read(&write(&MapFile::new(&map, Header::default())).unwrap()).unwrap();
+        let (cells, _) = read(&write_cells(&map).unwrap()).unwrap();
+        let (legacy, _) = read(&write_legacy(&map).unwrap()).unwrap();
+        for read in [&schema, &cells, &legacy] {
+            assert_eq!(read.adjacency(), map.adjacency());
+        }
+        // only the legacy layout leaves out the colors
+        assert_eq!(colors(&schema), colors(&map));
+        assert_eq!(colors(&cells), colors(&map));
+        assert!(legacy.0.iter().all(|c| c.color.is_none()));
+    }
+
+    #[test]
+    fn cells_cant_link_past_the_end() {
+        let s = r#"[{"name": "A", "connections": [1], "color": null}]"#;
+        assert!(matches!(read(s), Err(MapError::UnknownNeighbor { .. })));
+    }
+}
@@ -0,0 +1,280 @@
+use std::{fs, path::Path};
+
+use crate::{cell::Map, error::MapError, validation::ValidationReport};
+
+pub mod csv;
+pub mod dimacs;
+pub mod dot;
+pub mod geojson;
+pub mod json;
+pub mod schema;
+pub mod svg;
+
+use geojson::GeoJsonOptions;
+use schema::{Header, MapFile};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Toml,
+    Json,
+    Dimacs,  // the .col graph format used by graph coloring benchmarks
+    Dot,     // Graphviz, only for writing
+    Csv,     // an edge list or an adjacency matrix, written as an edge list
+    GeoJson, // polygons that are linked where their borders meet, only for reading
+    Svg,     // a drawing of the colored map, only for writing
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        // the format for a name given by the user, like "json"
+        match name.to_lowercase().as_str() {
+            "toml" => Some(Format::Toml),
+            "json" => Some(Format::Json),
+            "col" | "dimacs" => Some(Format::Dimacs),
+            "dot" | "gv" => Some(Format::Dot),
+            "csv" => Some(Format::Csv),
+            "geojson" => Some(Format::GeoJson),
+            "svg" => Some(Format::Svg),
+            _ => None,
+        }
+    }
+    pub fn from_path(file: &Path) -> Option<Format> {
+        // the format a file should be in, going by its extension
+        Format::from_name(file.extension()?.to_str()?)
+    }
+    pub fn can_read(&self) -> bool {
+        // some formats are only there to export to
+        !matches!(self, Format::Dot | Format::Svg)
+    }
+    pub fn can_write(&self) -> bool {
+        // and some only to import from
+        *self != Format::GeoJson
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LoadOptions {
+    pub repair: bool,            // fix link problems instead of failing
+    pub geojson: GeoJsonOptions, // how to find the neighbors in a GeoJSON file
+}
+
+pub struct Loaded {
+    pub map: Map,
+    pub header: Header, // the header of the file, or a default one for legacy files
+    pub report: ValidationReport, // the problems that were repaired while loading
+}
+
+pub fn load(file: &Path, repair: bool) -> Result<Loaded, MapError> {
+    load_with(
+        file,
+        &LoadOptions {
+            repair,
+            ..Default::default()
+        },
+    )
+}
+
+pub fn load_with(file: &Path, options: &LoadOptions) -> Result<Loaded, MapError> {
+    // load a map file, going by its extension. TOML files are either the
+    // versioned schema (with a [header] table) or the legacy format of region
+    // names to lists of neighbor names
+    let s = fs::read_to_string(file)?;
+    let (map, mut header) = match Format::from_path(file) {
+        Some(Format::Dimacs) => (dimacs::read(&s)?, Header::default()),
+        Some(Format::Json) => json::read(&s)?,
+        Some(Format::Csv) => (csv::read(&s)?, Header::default()),
+        Some(Format::GeoJson) => (geojson::read(&s, &options.geojson)?, Header::default()),
+        Some(format) if !format.can_read() => {
+            return Err(MapError::Parse(format!(
+                "{:?} files can only be written, not loaded",
+                format
+            )))
+        }
+        _ => {
+            let value: toml::Value = toml::from_str(&s)?;
+            if value.get("header").is_some_and(|h| h.is_table()) {
+                value.try_into::<MapFile>()?.into_map()?
+            } else {
+                (Map::link_names(legacy_regions(value)?)?, Header::default())
+            }
+        }
+    };
+    if header.name.is_empty() {
+        // fall back to the name of the file
+        if let Some(stem) = file.file_stem() {
+            header.name = stem.to_string_lossy().to_string();
+        }
+    }
+    let (map, report) = map.checked(options.repair)?;
+    Ok(Loaded {
+        map,
+        header,
+        report,
+    })
+}
+
+fn legacy_regions(value: toml::Value) -> Result<Vec<(String, Vec<String>)>, MapError> {
+    // read the legacy format in the order the regions are written in the file,
+    // so the cells (and with them the coloring) are the same on every load
+    let table = match value {
+        toml::Value::Table(table) => table,
+        _ => return Err(MapError::Parse("expected a table of regions".to_string())),
+    };
+    let mut regions = Vec::new();
+    for (name, neighbors) in table {
+        let neighbors: Vec<String> = neighbors.try_into().map_err(|e| {
+            MapError::Parse(format!("the neighbors of \"{}\" are invalid: {}", name, e))
+        })?;
+        regions.push((name, neighbors));
+    }
+    Ok(regions)
+}
+
+pub fn to_string(map: &Map, header: Header, format: Format) -> Result<String, MapError> {
+    // write the map in the versioned schema, keeping colors and metadata
+    let file = MapFile::new(map, header);
+    match format {
+        Format::Toml => toml::to_string(&file).map_err(|e| MapError::Unsupported(e.to_string())),
+        Format::Json => json::write(&file),
+        Format::Dimacs => Ok(dimacs::write(map, &file.header.name)),
+        Format::Dot => Ok(dot::write(map, &file.header.name)),
+        Format::Svg => Ok(svg::write(map, &file.header.name, file.header.k)),
+        Format::Csv => Ok(csv::write_edge_list(map)),
+        Format::GeoJson => Err(MapError::Unsupported(
+            "GeoJSON files can only be loaded, not written".to_string(),
+        )),
+    }
+}
+
+pub fn to_legacy_string(map: &Map, format: Format) -> Result<String, MapError> {
+    // write the map in the legacy format of region names to neighbor names,
+    // in map order so the output is the same every time
+    match format {
+        Format::Json => json::write_legacy(map),
+        Format::Toml => {
+            let mut table = toml::value::Table::new();
+            for (name, neighbors) in map.adjacency() {
+                table.insert(name, neighbors.into());
+            }
+            toml::to_string(&table).map_err(|e| MapError::Unsupported(e.to_string()))
+        }
+        _ => Err(MapError::Unsupported(
+            "only TOML and JSON have a legacy layout".to_string(),
+        )),
+    }
+}
+
+pub fn save(file: &Path, map: &Map, header: Header) -> Result<(), MapError> {
+    // write the map to a file in the format its extension asks for, or TOML
+    let format = Format::from_path(file).unwrap_or(Format::Toml);
+    fs::write(file, to_string(map, header, format)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::graph;
+    use std::path::PathBuf;
+
+    fn temp(name: &str) -> PathBuf {
+        // a file for a test to write to, that no other test run uses
+        std::env::temp_dir().join(format!("map-coloring-{}-{}", std::process::id(), name))
+    }
+
+    fn colored() -> (Map, Header) {
+        // a colored path with some metadata and a pin
+        let mut map = graph(3, &[(0, 1), (1, 2)]);
+        for (i, cell) in map.0.iter_mut().enumerate() {
+            cell.color = Some(i % 2);
+        }
+        map.0[0].label = Some("first".to_string());
+        map.0[1].description = Some("in the middle".to_string());
+        map.0[2].pinned = Some(0);
+        let header = Header {
+            name: "path".to_string(),
+            k: Some(2),
+            solver: Some("DSATUR".to_string()),
+            ..Header::default()
+        };
+        (map, header)
+    }
+
+    fn same(a: &Map, b: &Map) {
+        // the same regions, links, colors and metadata in the same order
+        assert_eq!(a.adjacency(), b.adjacency());
+        for (x, y) in a.0.iter().zip(b.0.iter()) {
+            assert_eq!(x.color, y.color);
+            assert_eq!(x.label, y.label);
+            assert_eq!(x.description, y.description);
+            assert_eq!(x.pinned, y.pinned);
+        }
+    }
+
+    fn round_trip(extension: &str) {
+        // save the colored map and load it back
+        let (map, header) = colored();
+        let file = temp(&format!("saved.{}", extension));
+        save(&file, &map, header).unwrap();
+        let loaded = load(&file, false).unwrap();
+        fs::remove_file(&file).unwrap();
+        same(&map, &loaded.map);
+        assert_eq!(loaded.header.name, "path");
+        assert_eq!(loaded.header.k, Some(2));
+        assert_eq!(loaded.header.solver.as_deref(), Some("DSATUR"));
+    }
+
+    #[test]
+    fn saved_maps_load_the_same() {
+        round_trip("toml");
+        round_trip("json");
+    }
+
+    #[test]
+    fn regions_keep_the_order_of_the_file() {
+        let file = Path::new("maps/canda.toml");
+        let loaded = load(file, false).unwrap();
+        let names: Vec<&str> = loaded.map.0.iter().map(|c| c.name.as_str()).collect();
+        // every line of the legacy file is a region, in the order they are written
+        let contents = fs::read_to_string(file).unwrap();
+        let written: Vec<&str> = contents
+            .lines()
+            .filter_map(|l| l.split_once(" = "))
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, written);
+    }
+
+    #[test]
+    fn loading_and_coloring_twice_gives_the_same_output() {
+        let output = || {
+            let mut loaded = load(Path::new("maps/canda.toml"), false).unwrap();
+            let solution = crate::solver::Strategy::Dsatur
+                .solver()
+                .solve(&loaded.map, 4);
+            solution.apply(&mut loaded.map);
+            to_string(&loaded.map, loaded.header, Format::Toml).unwrap()
+        };
+        assert_eq!(output(), output());
+    }
+
+    #[test]
+    fn legacy_maps_still_load() {
+        let loaded = load(Path::new("maps/usa.toml"), false).unwrap();
+        assert!(!loaded.map.0.is_empty());
+        assert!(loaded.map.0.iter().all(|c| c.color.is_none()));
+        // without a header the name comes from the file
+        assert_eq!(loaded.header.name, "usa");
+    }
+
+    #[test]
+    fn formats_that_cant_be_written_say_so() {
+        let map = Map::default();
+        for result in [
+            to_string(&map, Header::default(), Format::GeoJson),
+            to_legacy_string(&map, Format::Csv),
+        ] {
+            assert!(matches!(result, Err(MapError::Unsupported(_))));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{cell::Map, error::MapError};
+
+pub const VERSION: u32 = 1; // the newest version of the file schema
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Header {
+    pub version: u32, // the version of the schema the file was written with
+    #[serde(default)]
+    pub name: String, // the name of the map
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k: Option<usize>, // the number of colors the map was colored with (if any)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solver: Option<String>, // the solver that colored the map (if any)
+}
+
+impl Default for Header {
+    fn default() -> Header {
+        Header {
+            version: VERSION,
+            name: String::new(),
+            k: None,
+            solver: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Region {
+    pub name: String,
+    #[serde(default)]
+    pub neighbors: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<usize>, // the color the region has to keep
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MapFile {
+    pub header: Header,
+    #[serde(default)]
+    pub regions: Vec<Region>,
+}
+
+impl MapFile {
+    pub fn new(map: &Map, header: Header) -> MapFile {
+        // write down every cell with its neighbors by name, in map order
+        let regions = map
+            .0
+            .iter()
+            .map(|cell| Region {
+                name: cell.name.clone(),
+                neighbors: cell
+                    .connections
+                    .iter()
+                    .map(|c| map.0[*c].name.clone())
+                    .collect(),
+                color: cell.color,
+                label: cell.label.clone(),
+                description: cell.description.clone(),
+                pinned: cell.pinned,
+            })
+            .collect();
+        MapFile { header, regions }
+    }
+    pub fn into_map(self) -> Result<(Map, Header), MapError> {
+        // turn the file back into a map (the links are not validated here)
+        if self.header.version > VERSION {
+            return Err(MapError::Parse(format!(
+                "the file uses version {} of the map format, but only up to {} is supported",
+                self.header.version, VERSION
+            )));
+        }
+        let mut map = Map::link_names(
+            self.regions
+                .iter()
+                .map(|r| (r.name.clone(), r.neighbors.clone()))
+                .collect(),
+        )?;
+        for (cell, region) in map.0.iter_mut().zip(self.regions) {
+            cell.color = region.color;
+            cell.label = region.label;
+            cell.description = region.description;
+            cell.pinned = region.pinned;
+        }
+        Ok((map, self.header))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_versions_are_refused() {
+        let file = MapFile {
+            header: Header {
+                version: VERSION + 1,
+                ..Header::default()
+            },
+            regions: Vec::new(),
+        };
+        assert!(matches!(file.into_map(), Err(MapError::Parse(_))));
+    }
+}
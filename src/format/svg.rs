@@ -0,0 +1,112 @@
+use crate::{cell::Map, layout::layout, palette::palette};
+
+const NODE_SIZE: f32 = 22.; // the radius of a cell
+const MARGIN: f32 = 40.;
+const LEGEND_ROW: f32 = 24.;
+
+fn escape(s: &str) -> String {
+    // text that is safe to put inside an XML element or attribute
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn write(map: &Map, name: &str, k: Option<usize>) -> String {
+    // draw the map as a standalone SVG: the links as lines, the cells as
+    // labeled circles in their colors and a legend with the palette below
+    let positions = layout(map);
+    let size = (160. * (map.0.len() as f32).sqrt()).max(400.);
+    // the legend lists every color the map could use, and any that it does use
+    let used = map
+        .0
+        .iter()
+        .filter_map(|c| c.color)
+        .max()
+        .map_or(0, |c| c + 1);
+    let colors = palette(k.unwrap_or(0).max(used));
+    let uncolored = map.0.iter().any(|c| c.color.is_none());
+    let legend_rows = colors.len() + uncolored as usize;
+    let width = size + 2. * MARGIN;
+    let height = size + 2. * MARGIN + legend_rows as f32 * LEGEND_ROW + 20.;
+    let at = |i: usize| {
+        (
+            MARGIN + positions[i].0 * size,
+            MARGIN + positions[i].1 * size,
+        )
+    };
+
+    let mut s = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"0 0 {w:.0} {h:.0}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+        w = width,
+        h = height
+    );
+    s += &format!("  <title>{}</title>\n", escape(name));
+    s += "  <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n";
+    s += "  <g stroke=\"#888888\" stroke-width=\"2\">\n";
+    for (i, cell) in map.0.iter().enumerate() {
+        // every link is listed by both cells, only draw it once
+        for j in cell.connections.iter().filter(|j| **j > i) {
+            let (a, b) = (at(i), at(*j));
+            s += &format!(
+                "    <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>\n",
+                a.0, a.1, b.0, b.1
+            );
+        }
+    }
+    s += "  </g>\n";
+    for (i, cell) in map.0.iter().enumerate() {
+        let (x, y) = at(i);
+        let color = cell.color();
+        let label = cell.label.as_ref().unwrap_or(&cell.name);
+        s += "  <g>\n";
+        // the description (or the name) shows when hovering the cell
+        s += &format!(
+            "    <title>{}</title>\n",
+            escape(cell.description.as_ref().unwrap_or(&cell.name))
+        );
+        s += &format!(
+            "    <circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\" stroke=\"black\"/>\n",
+            x,
+            y,
+            NODE_SIZE,
+            color.hex()
+        );
+        s += &format!(
+            "    <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>\n",
+            x,
+            y,
+            color.contrast().hex(),
+            escape(label)
+        );
+        s += "  </g>\n";
+    }
+    // the legend
+    let mut y = size + 2. * MARGIN;
+    let mut row = |fill: String, text: String| {
+        let r = format!(
+            "  <rect x=\"{}\" y=\"{:.1}\" width=\"16\" height=\"16\" fill=\"{}\" stroke=\"black\"/>\n  <text x=\"{}\" y=\"{:.1}\" dominant-baseline=\"central\">{}</text>\n",
+            MARGIN,
+            y,
+            fill,
+            MARGIN + 24.,
+            y + 8.,
+            text
+        );
+        y += LEGEND_ROW;
+        r
+    };
+    for (i, color) in colors.iter().enumerate() {
+        let count = map.0.iter().filter(|c| c.color == Some(i)).count();
+        s += &row(color.hex(), format!("color {} ({} regions)", i + 1, count));
+    }
+    if uncolored {
+        let count = map.0.iter().filter(|c| c.color.is_none()).count();
+        s += &row(
+            "#000000".to_string(),
+            format!("uncolored ({} regions)", count),
+        );
+    }
+    s += "</svg>\n";
+    s
+}
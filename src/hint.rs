@@ -0,0 +1,120 @@
+use crate::{cell::Map, palette::color_name};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    Solved,                    // every region is colored and no neighbors clash
+    Conflict(usize, usize),    // two neighbors already have the same color
+    Stuck(usize),              // a region that has no color left
+    Forced(usize, usize),      // a region that can only get one color
+    Choice(usize, Vec<usize>), // nothing is forced, this region has the fewest colors left
+}
+
+fn options(map: &Map, cell: usize, k: usize) -> Vec<usize> {
+    // the colors none of the neighbors of the cell have, like Cell::get_avalible
+    (0..k)
+        .filter(|c| {
+            map.0[cell]
+                .connections
+                .iter()
+                .all(|n| map.0[*n].color != Some(*c))
+        })
+        .collect()
+}
+
+pub fn hint(map: &Map, k: usize) -> Hint {
+    // find the most useful thing to tell about a partly colored map: a mistake
+    // that was already made, a region that can no longer be colored, a region
+    // that can only be colored one way, or else where to guess next
+    if let Some((a, b)) = map.conflicts().first() {
+        return Hint::Conflict(*a, *b);
+    }
+    let mut best: Option<(usize, Vec<usize>)> = None;
+    for i in (0..map.0.len()).filter(|i| map.0[*i].color.is_none()) {
+        let options = options(map, i, k);
+        match options.len() {
+            0 => return Hint::Stuck(i),
+            1 => return Hint::Forced(i, options[0]),
+            _ => {
+                if best.as_ref().is_none_or(|(_, b)| options.len() < b.len()) {
+                    best = Some((i, options));
+                }
+            }
+        }
+    }
+    match best {
+        Some((i, options)) => Hint::Choice(i, options),
+        None => Hint::Solved,
+    }
+}
+
+fn blockers(map: &Map, cell: usize, k: usize) -> String {
+    // which neighbors use up which colors, like "red by A, green by B and C"
+    let mut parts = Vec::new();
+    for c in 0..k {
+        let names: Vec<String> = map.0[cell]
+            .connections
+            .iter()
+            .filter(|n| map.0[**n].color == Some(c))
+            .map(|n| map.0[*n].name.clone())
+            .collect();
+        if !names.is_empty() {
+            parts.push(format!("{} is used by {}", color_name(c), join(&names)));
+        }
+    }
+    join(&parts)
+}
+
+fn join(items: &[String]) -> String {
+    // a list the way you would write it out, "a, b and c"
+    match items.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.to_string(),
+        Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+impl Hint {
+    pub fn explain(&self, map: &Map, k: usize) -> String {
+        // say what the hint means in plain words, naming the neighbors involved
+        let name = |i: usize| map.0[i].name.as_str();
+        match self {
+            Hint::Solved => "The map is fully colored and no neighbors share a color.".to_string(),
+            Hint::Conflict(a, b) => format!(
+                "{} and {} border each other but are both {}, one of them has to change.",
+                name(*a),
+                name(*b),
+                color_name(map.0[*a].color.unwrap_or(0))
+            ),
+            Hint::Stuck(i) => format!(
+                "{} can not be colored anymore: {}. One of its neighbors has to change.",
+                name(*i),
+                blockers(map, *i, k)
+            ),
+            Hint::Forced(i, c) => {
+                let blocked = blockers(map, *i, k);
+                if blocked.is_empty() {
+                    format!(
+                        "{} has to be {}, it is the only color.",
+                        name(*i),
+                        color_name(*c)
+                    )
+                } else {
+                    format!("{} has to be {}: {}.", name(*i), color_name(*c), blocked)
+                }
+            }
+            Hint::Choice(i, options) => {
+                let colors: Vec<String> = options.iter().map(|c| color_name(*c)).collect();
+                let blocked = blockers(map, *i, k);
+                let mut s = format!(
+                    "Nothing is forced yet. {} has the fewest choices left: {}",
+                    name(*i),
+                    join(&colors)
+                );
+                if !blocked.is_empty() {
+                    s += &format!(" (since {})", blocked);
+                }
+                s + "."
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::cell::Map;
+
+pub type Point = (f32, f32);
+
+fn iterations(n: usize) -> usize {
+    // every step compares every pair of cells, so big maps get fewer steps
+    (20_000_000 / (n * n).max(1)).clamp(10, 300)
+}
+
+pub fn layout(map: &Map) -> Vec<Point> {
+    // place the cells with a force directed (Fruchterman-Reingold) layout:
+    // every cell pushes every other cell away and every link pulls its two
+    // cells together, while the steps get smaller until things settle down.
+    // gives a position between 0 and 1 on both axes for every cell, and the
+    // same positions every time for the same map
+    let n = map.0.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    // start on a circle, so the layout doesnt depend on any randomness
+    let mut positions: Vec<Point> = (0..n)
+        .map(|i| {
+            let angle = i as f32 / n as f32 * std::f32::consts::TAU;
+            (0.5 + 0.4 * angle.cos(), 0.5 + 0.4 * angle.sin())
+        })
+        .collect();
+    let ideal = (1. / n as f32).sqrt(); // the distance the cells want to be apart
+    let steps = iterations(n);
+    let mut temperature = 0.1;
+    for step in 0..steps {
+        let mut moves = vec![(0f32, 0f32); n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let (dx, dy) = (
+                    positions[i].0 - positions[j].0,
+                    positions[i].1 - positions[j].1,
+                );
+                let distance = (dx * dx + dy * dy).sqrt().max(1e-4);
+                let force = ideal * ideal / distance;
+                moves[i].0 += dx / distance * force;
+                moves[i].1 += dy / distance * force;
+                moves[j].0 -= dx / distance * force;
+                moves[j].1 -= dy / distance * force;
+            }
+        }
+        for (i, cell) in map.0.iter().enumerate() {
+            // every link is listed by both cells, so each side only pulls itself
+            for j in cell.connections.iter() {
+                let (dx, dy) = (
+                    positions[i].0 - positions[*j].0,
+                    positions[i].1 - positions[*j].1,
+                );
+                let distance = (dx * dx + dy * dy).sqrt().max(1e-4);
+                let force = distance * distance / ideal;
+                moves[i].0 -= dx / distance * force;
+                moves[i].1 -= dy / distance * force;
+            }
+            // a little pull to the middle keeps separate components close
+            moves[i].0 -= (positions[i].0 - 0.5) * ideal;
+            moves[i].1 -= (positions[i].1 - 0.5) * ideal;
+        }
+        for (p, m) in positions.iter_mut().zip(moves) {
+            let length = (m.0 * m.0 + m.1 * m.1).sqrt().max(1e-9);
+            let length_limited = length.min(temperature);
+            p.0 += m.0 / length * length_limited;
+            p.1 += m.1 / length * length_limited;
+        }
+        temperature = 0.1 * (1. - (step + 1) as f32 / steps as f32) + 0.001;
+    }
+    normalize(positions)
+}
+
+fn normalize(mut positions: Vec<Point>) -> Vec<Point> {
+    // stretch the positions to fill 0 to 1, keeping the aspect ratio
+    let (mut min, mut max) = (positions[0], positions[0]);
+    for p in positions.iter() {
+        min = (min.0.min(p.0), min.1.min(p.1));
+        max = (max.0.max(p.0), max.1.max(p.1));
+    }
+    let size = (max.0 - min.0).max(max.1 - min.1);
+    if size < 1e-6 {
+        // a single cell (or all on one spot) goes in the middle
+        return vec![(0.5, 0.5); positions.len()];
+    }
+    // center the shorter side
+    let offset = ((size - (max.0 - min.0)) / 2., (size - (max.1 - min.1)) / 2.);
+    for p in positions.iter_mut() {
+        p.0 = (p.0 - min.0 + offset.0) / size;
+        p.1 = (p.1 - min.1 + offset.1) / size;
+    }
+    positions
+}
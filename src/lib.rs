@@ -0,0 +1,15 @@
+// the map model, its file formats and the solvers, without any gui
+pub mod bench;
+pub mod cell;
+pub mod error;
+pub mod format;
+pub mod hint;
+pub mod layout;
+pub mod palette;
+pub mod pin;
+pub mod solver;
+pub mod trace;
+pub mod validation;
+
+#[cfg(test)]
+mod test_util;
@@ -1,14 +1,35 @@
+use std::process::ExitCode;
+
 // import some things
+#[cfg(feature = "gui")]
 mod app;
-mod cell;
+mod cli;
+#[cfg(feature = "gui")]
 mod scene;
+#[cfg(feature = "gui")]
 mod utility;
 
-fn main() {
+fn main() -> ExitCode {
+    // with a command (like `map-coloring color maps/usa.toml`) there is no window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return cli::run(&args);
+    }
+    run_app()
+}
+
+#[cfg(feature = "gui")]
+fn run_app() -> ExitCode {
     // create the app
     let app = app::MapColoringApp::default();
     // create random stuff for running the app
     let native_options = eframe::NativeOptions::default();
     // run the app
-    eframe::run_native(Box::new(app), native_options);
+    eframe::run_native(Box::new(app), native_options)
+}
+
+#[cfg(not(feature = "gui"))]
+fn run_app() -> ExitCode {
+    // built without the gui, so all there is to do is explain the commands
+    cli::run(&["help".to_string()])
 }
@@ -0,0 +1,83 @@
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Rgb(pub u8, pub u8, pub u8); // a display color, without depending on the gui
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+
+    pub fn hex(&self) -> String {
+        // the color as #rrggbb, for files like SVG or DOT
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+    pub fn contrast(&self) -> Rgb {
+        // black or white, whichever is easier to read on top of this color
+        let luma = 0.299 * self.0 as f32 + 0.587 * self.1 as f32 + 0.114 * self.2 as f32;
+        if luma > 140. {
+            Rgb::BLACK
+        } else {
+            Rgb(255, 255, 255)
+        }
+    }
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Rgb {
+        // h, s and v all go from 0 to 1
+        let h = (h.fract() + 1.).fract() * 6.;
+        let c = v * s;
+        let x = c * (1. - (h % 2. - 1.).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.),
+            1 => (x, c, 0.),
+            2 => (0., c, x),
+            3 => (0., x, c),
+            4 => (x, 0., c),
+            _ => (c, 0., x),
+        };
+        let m = v - c;
+        let byte = |f: f32| ((f + m) * 255.).round() as u8;
+        Rgb(byte(r), byte(g), byte(b))
+    }
+}
+
+#[cfg(feature = "gui")]
+impl From<Rgb> for egui::Color32 {
+    fn from(c: Rgb) -> egui::Color32 {
+        egui::Color32::from_rgb(c.0, c.1, c.2)
+    }
+}
+
+pub const COLORS: [Rgb; 4] = [
+    // these are the colours that will be displayed first
+    Rgb(255, 0, 0),
+    Rgb(0, 255, 0),
+    Rgb(0, 0, 255),
+    Rgb(255, 0, 255),
+];
+
+pub const NAMES: [&str; 4] = ["red", "green", "blue", "magenta"]; // the names of COLORS
+
+pub fn color_name(i: usize) -> String {
+    // a name for color number i, to use in text
+    match NAMES.get(i) {
+        Some(name) => name.to_string(),
+        None => format!("color {}", i + 1),
+    }
+}
+
+pub fn color_at(i: usize) -> Rgb {
+    // gives the display color of color number i
+    if let Some(c) = COLORS.get(i) {
+        return *c;
+    }
+    // past the fixed colours we step around the hue circle by the golden ratio,
+    // which keeps every new colour far away from the ones before it
+    let hue = ((i - COLORS.len()) as f32 * 0.618_034 + 0.08) % 1.;
+    let value = if (i - COLORS.len()).is_multiple_of(2) {
+        0.95
+    } else {
+        0.7
+    };
+    Rgb::from_hsv(hue, 0.8, value)
+}
+
+pub fn palette(k: usize) -> Vec<Rgb> {
+    // gives the first k display colors
+    (0..k).map(color_at).collect()
+}
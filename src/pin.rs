@@ -0,0 +1,212 @@
+use crate::{
+    cell::Map,
+    palette::color_name,
+    solver::{control::Control, Solution, Strategy},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PinProblem {
+    OutOfRange(usize),   // a cell is pinned to a color past the k colors in use
+    Clash(usize, usize), // two neighbors are pinned to the same color
+    Boxed(usize),        // the pinned neighbors of a cell use up every color
+}
+
+pub fn problems(map: &Map, k: usize) -> Vec<PinProblem> {
+    // the ways the pins alone keep the map from being colored with k colors,
+    // that can be seen without searching. pins out of range come first, since
+    // they are the real problem when they also leave a neighbor boxed in
+    let mut problems: Vec<PinProblem> = map
+        .0
+        .iter()
+        .enumerate()
+        .filter(|(_, cell)| cell.pinned.is_some_and(|c| c >= k))
+        .map(|(i, _)| PinProblem::OutOfRange(i))
+        .collect();
+    for (i, cell) in map.0.iter().enumerate() {
+        match cell.pinned {
+            Some(c) if c >= k => {}
+            Some(c) => {
+                for j in cell.connections.iter().filter(|j| **j > i) {
+                    if map.0[*j].pinned == Some(c) {
+                        problems.push(PinProblem::Clash(i, *j));
+                    }
+                }
+            }
+            None => {
+                let free =
+                    (0..k).any(|c| cell.connections.iter().all(|n| map.0[*n].pinned != Some(c)));
+                if !free {
+                    problems.push(PinProblem::Boxed(i));
+                }
+            }
+        }
+    }
+    problems
+}
+
+fn colors(k: usize) -> String {
+    // "1 color" or "k colors"
+    format!("{} color{}", k, if k == 1 { "" } else { "s" })
+}
+
+impl PinProblem {
+    pub fn explain(&self, map: &Map, k: usize) -> String {
+        // say what is wrong with the pins, naming the regions involved
+        let name = |i: usize| map.0[i].name.as_str();
+        let pin = |i: usize| color_name(map.0[i].pinned.unwrap_or(0));
+        match self {
+            PinProblem::OutOfRange(i) => format!(
+                "{} is pinned to {}, but only {} can be used",
+                name(*i),
+                pin(*i),
+                colors(k)
+            ),
+            PinProblem::Clash(a, b) => format!(
+                "{} and {} border each other but are both pinned to {}",
+                name(*a),
+                name(*b),
+                pin(*a)
+            ),
+            PinProblem::Boxed(i) => {
+                let pinned: Vec<String> = map.0[*i]
+                    .connections
+                    .iter()
+                    .filter(|n| map.0[**n].pinned.is_some_and(|c| c < k))
+                    .map(|n| format!("{} ({})", name(*n), pin(*n)))
+                    .collect();
+                format!(
+                    "{} has no color left, its pinned neighbors {} use up {}",
+                    name(*i),
+                    pinned.join(", "),
+                    if k == 1 {
+                        "the only color".to_string()
+                    } else {
+                        format!("all {}", colors(k))
+                    }
+                )
+            }
+        }
+    }
+}
+
+pub fn blame(map: &Map, k: usize, control: &Control) -> Option<String> {
+    // after a map could not be colored, tell if the pins are the reason.
+    // gives None if there are no pins, if an exact search finds the map can be
+    // colored with the pins after all (or the control stops it), or if the map
+    // cant be colored without them either
+    if map.0.iter().all(|c| c.pinned.is_none()) {
+        return None;
+    }
+    if let Some(problem) = problems(map, k).first() {
+        return Some(format!(
+            "The pinned colors make the map uncolorable: {}",
+            problem.explain(map, k)
+        ));
+    }
+    // the failure may have come from a solver that isnt exact, so make sure
+    // it really cant be done with the pins before blaming them
+    let mut pinned = map.clone();
+    pinned.reset_colors();
+    let solution = Strategy::Engine.solver().solve_with(&pinned, k, control);
+    if solution.report.colored || solution.report.unknown() {
+        return None;
+    }
+    // then try again without the pins
+    let mut free = map.clone();
+    for cell in free.0.iter_mut() {
+        cell.pinned = None;
+        cell.color = None;
+    }
+    let solution = Strategy::Engine.solver().solve_with(&free, k, control);
+    solution.report.colored.then(|| {
+        format!(
+            "The pinned colors make the map uncolorable, without them it can be colored with {}",
+            colors(k)
+        )
+    })
+}
+
+pub fn fewest_colors(map: &Map, from: usize, control: &Control) -> Option<(usize, Solution)> {
+    // the fewest colors, from `from` up, that color the map while keeping its pins,
+    // with the coloring. gives None if two pinned neighbors clash (then no number
+    // of colors works) or if the control stopped the search.
+    // clashes are the same for any k, so check with as many colors as there can be
+    if problems(map, usize::MAX)
+        .iter()
+        .any(|p| matches!(p, PinProblem::Clash(_, _)))
+    {
+        return None;
+    }
+    // every pinned color has to be one of the colors
+    let highest = map.0.iter().filter_map(|c| c.pinned).max();
+    let mut k = from.max(highest.map_or(0, |c| c + 1)).max(1);
+    let mut pinned = map.clone();
+    pinned.reset_colors();
+    loop {
+        // with no clashes, enough colors always work, so this ends
+        let solution = Strategy::Engine.solver().solve_with(&pinned, k, control);
+        if solution.report.colored {
+            return Some((k, solution));
+        }
+        if solution.report.unknown() {
+            return None;
+        }
+        k += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{graph, welsh_powell_trap};
+
+    #[test]
+    fn blames_pins_that_cant_be_kept() {
+        // a path of four with both ends pinned to the same color cant take 2 colors,
+        // even though nothing is wrong right next to the pins
+        let mut map = graph(4, &[(0, 1), (1, 2), (2, 3)]);
+        map.0[0].pinned = Some(0);
+        map.0[3].pinned = Some(0);
+        assert!(problems(&map, 2).is_empty());
+        assert!(blame(&map, 2, &Control::default()).is_some());
+        assert!(blame(&map, 3, &Control::default()).is_none());
+    }
+
+    #[test]
+    fn doesnt_blame_pins_that_can_be_kept() {
+        // welsh-powell fails on this, but the pin agrees with a real coloring
+        let mut map = welsh_powell_trap();
+        let solution = Strategy::Engine.solver().solve(&map, 3);
+        map.0[4].pinned = solution.colors[4];
+        assert!(!Strategy::WelshPowell.solver().solve(&map, 3).report.colored);
+        assert!(blame(&map, 3, &Control::default()).is_none());
+    }
+
+    #[test]
+    fn pins_out_of_range_come_first() {
+        // the middle of the path is boxed in, but only because of the pin past k
+        let mut map = graph(3, &[(0, 1), (1, 2)]);
+        map.0[0].pinned = Some(0);
+        map.0[2].pinned = Some(1);
+        let found = problems(&map, 1);
+        assert_eq!(found, vec![PinProblem::OutOfRange(2), PinProblem::Boxed(1)]);
+        assert!(found[0]
+            .explain(&map, 1)
+            .ends_with("only 1 color can be used"));
+        assert!(found[1].explain(&map, 1).ends_with("use up the only color"));
+        assert!(!found[1].explain(&map, 1).contains("2 ("));
+    }
+
+    #[test]
+    fn fewest_colors_keeps_the_pins() {
+        // a triangle needs 3 colors, but one pinned to the fourth color needs 4
+        let mut map = graph(3, &[(0, 1), (1, 2), (0, 2)]);
+        assert_eq!(fewest_colors(&map, 1, &Control::default()).unwrap().0, 3);
+        map.0[2].pinned = Some(3);
+        let (k, solution) = fewest_colors(&map, 3, &Control::default()).unwrap();
+        assert_eq!(k, 4);
+        assert_eq!(solution.colors[2], Some(3));
+        map.0[1].pinned = Some(3);
+        assert!(fewest_colors(&map, 3, &Control::default()).is_none());
+    }
+}
@@ -1,6 +1,8 @@
-use egui::{Color32, RichText, Ui};
+use egui::{Color32, RichText, Stroke, Ui, Vec2};
 
-use crate::cell::Map;
+use map_coloring::{cell::Map, validation::Issue};
+
+use crate::utility::graph_view::GraphView;
 
 use super::{enter_names::EnterNames, generate_map::GenerateMap, Scene, SceneType};
 
@@ -8,6 +10,10 @@ use super::{enter_names::EnterNames, generate_map::GenerateMap, Scene, SceneType
 pub struct CreateLinks {
     map: Map,
     next: Box<Option<SceneType>>,
+    show_graph: bool, // edit the links on the graph instead of in menus
+    graph: GraphView,
+    linking_from: Option<usize>, // the cell a link is being dragged from
+    selected: Option<usize>,     // the cell whose name can be edited
 }
 
 impl Scene for CreateLinks {
@@ -15,39 +21,32 @@ impl Scene for CreateLinks {
         CreateLinks {
             map: Map::default(),
             next: Box::new(None),
+            show_graph: true,
+            graph: GraphView::default(),
+            linking_from: None,
+            selected: None,
         }
     }
     fn draw(&mut self, ui: &mut Ui) {
-        let mut change: Option<(usize, usize)> = None;
-        ui.columns(3, |cols| {
-            for (i, cell) in self.map.0.iter().enumerate() {
-                cols[1].vertical_centered(|ui| {
-                    ui.horizontal(|ui| {
-                        ui.group(|ui| {
-                            ui.label(RichText::new(cell.name.clone()).size(15.));
-                            ui.menu_button("Links", |ui| {
-                                for (j, cell2) in self.map.0.iter().enumerate() {
-                                    if j == i {
-                                        continue;
-                                    }
-                                    let mut is_checked = cell.connections.contains(&j);
-                                    let checkbox = ui.checkbox(&mut is_checked, cell2.name.clone());
-                                    if checkbox.changed() {
-                                        change = Some((i, j))
-                                    }
-                                }
-                            })
-                        });
-                    });
-                });
-            }
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.show_graph, true, "Graph");
+                ui.selectable_value(&mut self.show_graph, false, "List");
+                if self.show_graph && ui.button("Reset view").clicked() {
+                    self.graph.reset_view();
+                }
+            });
         });
-        if let Some((i, j)) = change {
-            self.map.0[i].link_changed(j);
-            self.map.0[j].link_changed(i);
+        if self.show_graph {
+            self.draw_graph(ui);
+        } else {
+            self.draw_list(ui);
         }
-        ui.add_space(520. - (self.map.0.len() as f32) * 33.);
+        let problem = self.name_problem();
         ui.vertical_centered(|ui| {
+            if let Some(problem) = &problem {
+                ui.colored_label(Color32::RED, problem);
+            }
             let button =
                 egui::Button::new(RichText::new("Go back").size(25.).color(Color32::WHITE))
                     .fill(Color32::RED);
@@ -55,16 +54,17 @@ impl Scene for CreateLinks {
             if resp.clicked() {
                 let mut next_scene = EnterNames::new();
                 next_scene.set_names(self.map.0.iter().map(|t| t.name.clone()).collect());
-                self.next = Box::new(Some(SceneType::EnterNames(next_scene)))
+                *self.next = Some(SceneType::EnterNames(next_scene))
             }
             ui.add_space(10.);
             let button =
                 egui::Button::new(RichText::new("Next Step").size(25.).color(Color32::WHITE))
                     .fill(Color32::BLUE);
-            let resp = ui.add(button);
+            // a map with empty or repeated names cant be saved and loaded again
+            let resp = ui.add_enabled(problem.is_none(), button);
             if resp.clicked() {
                 let next_scene = GenerateMap::from(self.map.clone());
-                self.next = Box::new(Some(SceneType::GenerateMap(next_scene)))
+                *self.next = Some(SceneType::GenerateMap(next_scene))
             }
         });
     }
@@ -80,4 +80,126 @@ impl CreateLinks {
         }
         self
     }
+    fn name_problem(&self) -> Option<String> {
+        // the first region name that would make the map invalid, if any
+        if let Some(i) = self.map.0.iter().position(|c| c.name.trim().is_empty()) {
+            return Some(format!("Region {} needs a name", i + 1));
+        }
+        self.map
+            .validation_report()
+            .issues
+            .into_iter()
+            .find(|issue| matches!(issue, Issue::DuplicateName(_)))
+            .map(|issue| issue.to_string())
+    }
+    fn toggle_link(&mut self, i: usize, j: usize) {
+        // links go both ways, so change both cells
+        self.map.0[i].link_changed(j);
+        self.map.0[j].link_changed(i);
+    }
+    fn draw_list(&mut self, ui: &mut Ui) {
+        // a menu of checkboxes per cell, one for every other cell
+        let mut change: Option<(usize, usize)> = None;
+        ui.columns(3, |cols| {
+            for (i, cell) in self.map.0.iter().enumerate() {
+                cols[1].vertical_centered(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.group(|ui| {
+                            ui.label(RichText::new(cell.name.clone()).size(15.));
+                            ui.menu_button("Links", |ui| {
+                                for (j, cell2) in self.map.0.iter().enumerate() {
+                                    if j == i {
+                                        continue;
+                                    }
+                                    let mut is_checked = cell.connections.contains(&j);
+                                    let checkbox = ui.checkbox(&mut is_checked, cell2.name.clone());
+                                    if checkbox.changed() {
+                                        change = Some((i, j))
+                                    }
+                                }
+                            })
+                        });
+                    });
+                });
+            }
+        });
+        if let Some((i, j)) = change {
+            self.toggle_link(i, j);
+        }
+        ui.add_space(520. - (self.map.0.len() as f32) * 33.);
+    }
+    fn draw_graph(&mut self, ui: &mut Ui) {
+        // click empty space to add a region, click a region to rename it and
+        // drag from one region to another to link or unlink them
+        ui.vertical_centered(|ui| {
+            ui.label("Click to add a region, drag between two regions to link or unlink them");
+        });
+        let size = Vec2::new(ui.available_width(), 420.);
+        let response = self.graph.show(ui, &self.map, size);
+        let pointer = ui.input().pointer.hover_pos();
+
+        if response.drag_started() {
+            let origin = ui.input().pointer.press_origin();
+            self.linking_from = origin.and_then(|p| self.graph.cell_at(&response, p));
+        }
+        if let (Some(from), Some(pointer)) = (self.linking_from, pointer) {
+            if response.dragged() {
+                // show the link that is being made
+                let start = self.graph.screen_pos(&response, self.graph.positions[from]);
+                ui.painter_at(response.rect).line_segment(
+                    [start, pointer],
+                    Stroke::new(2., Color32::from_rgb(255, 200, 0)),
+                );
+            }
+        }
+        if response.drag_released() {
+            let to = pointer.and_then(|p| self.graph.cell_at(&response, p));
+            if let (Some(from), Some(to)) = (self.linking_from, to) {
+                if from != to {
+                    self.toggle_link(from, to);
+                }
+            }
+            self.linking_from = None;
+        }
+        if response.clicked() {
+            if let Some(pointer) = pointer {
+                match self.graph.cell_at(&response, pointer) {
+                    Some(i) => self.selected = Some(i),
+                    None => {
+                        // a new region where the click was, with a name that isnt taken
+                        let mut n = self.map.0.len() + 1;
+                        while self.map.0.iter().any(|c| c.name == format!("Region {}", n)) {
+                            n += 1;
+                        }
+                        self.map.add_cell(format!("Region {}", n));
+                        let position = self.graph.map_pos(&response, pointer);
+                        self.graph.positions.push(position);
+                        self.selected = Some(self.map.0.len() - 1);
+                    }
+                }
+            }
+        }
+        ui.add_space(5.);
+        ui.columns(3, |cols| {
+            cols[1].horizontal(|ui| match self.selected {
+                Some(i) => {
+                    ui.label("Name:");
+                    let name = &self.map.0[i].name;
+                    let invalid = name.trim().is_empty()
+                        || self.map.0.iter().filter(|c| &c.name == name).count() > 1;
+                    let edit = egui::TextEdit::singleline(&mut self.map.0[i].name);
+                    // mark the name red while it is empty or already used
+                    if invalid {
+                        ui.add(edit.text_color(Color32::RED));
+                    } else {
+                        ui.add(edit);
+                    }
+                }
+                None => {
+                    ui.label("Click a region to rename it");
+                }
+            });
+        });
+        ui.add_space(10.);
+    }
 }
@@ -68,7 +68,7 @@ impl Scene for EnterNames {
             if resp.clicked() {
                 let mut next_scene = CreateLinks::new();
                 next_scene.add_names(new.clone());
-                self.next = Box::new(Some(SceneType::CreateLinks(next_scene)))
+                *self.next = Some(SceneType::CreateLinks(next_scene))
             }
         });
         self.names = new;
@@ -1,8 +1,41 @@
-use crate::{cell::Map, utility::file::get_next_file_path};
+use crate::utility::{file::get_next_file_path, graph_view::GraphView, replay::Replay};
 use egui::{Color32, RichText, Ui};
-use std::{collections::HashMap, fs::File, io::Write};
+use map_coloring::{
+    cell::{Chromatic, ColorReport, Map},
+    format::{self, schema::Header},
+    hint::hint,
+    palette::palette,
+    pin,
+    solver::{
+        background::{self, Job},
+        control::Control,
+        Solution, Strategy,
+    },
+    trace::Trace,
+};
+use std::{path::Path, rc::Rc, time::Duration};
 
-use super::{start::StartingScene, Scene, SceneType};
+use super::{puzzle::Puzzle, start::StartingScene, Scene, SceneType};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Task {
+    Color,     // the chosen solver colors the map
+    Compare,   // every solver colors a blank copy
+    Trace,     // the DFS is recorded for a replay
+    Chromatic, // the fewest colors are searched for
+    Blame,     // the pins are checked after the map couldnt be colored
+}
+
+// what a background job gives back once it is done
+enum Outcome {
+    Colored(Solution),
+    Compared(Vec<(Strategy, Solution)>),
+    Traced(Trace),
+    // the chromatic number and, if there are pins, the fewest colors that keep them.
+    // None if the search was stopped
+    Chromatic(Option<(Chromatic, Option<(usize, Solution)>)>),
+    Blamed(Option<String>),
+}
 
 #[derive(Clone)]
 pub struct GenerateMap {
@@ -11,6 +44,26 @@ pub struct GenerateMap {
     colorable: bool,
     saved: bool,
     iterations: usize,
+    report: Option<ColorReport>, // the per-component results of the last coloring
+    k: usize,                    // the number of colors to color the map with
+    chromatic: Option<Chromatic>, // the minimum coloring, if it was searched for
+    strategy: Strategy,          // the solver used by "Color the map"
+    solved_with: Strategy,       // the solver the current report came from
+    comparison: Vec<(Strategy, Solution)>, // the results of running every solver
+    warnings: Vec<String>,       // the problems that were repaired when loading
+    header: Header,              // the name of the map and how it was colored
+    error: Option<String>,       // the last error while saving, or a search that stopped
+    exported: Option<String>,    // the path of the last export
+    show_graph: bool,            // draw the map as a graph instead of a list of names
+    graph: GraphView,
+    hint: Option<String>,          // the explanation of the last hint
+    replay: Option<Replay>,        // a replay of the search, step by step
+    job: Option<Rc<Job<Outcome>>>, // the search that is running in the background
+    task: Task,                    // what the job is doing
+    nodes: usize,                  // how far the running solver got
+    time_limit: f32,               // how many seconds a solver may take, 0 for no limit
+    blame: Option<String>,         // why the pins keep the map from being colored
+    pin_note: Option<String>,      // what the pins change about the chromatic number
 }
 
 impl Scene for GenerateMap {
@@ -21,12 +74,80 @@ impl Scene for GenerateMap {
             colorable: true,
             iterations: 0,
             saved: false,
+            report: None,
+            k: 4,
+            chromatic: None,
+            strategy: Strategy::Dfs,
+            solved_with: Strategy::Dfs,
+            comparison: Vec::new(),
+            warnings: Vec::new(),
+            header: Header::default(),
+            error: None,
+            exported: None,
+            show_graph: true,
+            graph: GraphView::default(),
+            hint: None,
+            replay: None,
+            job: None,
+            task: Task::Color,
+            nodes: 0,
+            time_limit: 0.,
+            blame: None,
+            pin_note: None,
         }
     }
     fn draw(&mut self, ui: &mut Ui) {
+        self.poll(ui);
+        if !self.warnings.is_empty() {
+            ui.vertical_centered(|ui| {
+                ui.collapsing(
+                    RichText::new(format!(
+                        "Repaired {} problems when loading",
+                        self.warnings.len()
+                    ))
+                    .color(Color32::from_rgb(200, 120, 0)),
+                    |ui| {
+                        for warning in self.warnings.iter() {
+                            ui.label(warning);
+                        }
+                    },
+                );
+            });
+        }
         if !self.colorable {
             ui.vertical_centered(|ui| {
                 ui.colored_label(Color32::RED, "The current map is not colorable!");
+                if let Some(blame) = &self.blame {
+                    ui.colored_label(Color32::RED, blame);
+                }
+            });
+        }
+        if let Some(report) = self.report.as_ref().filter(|r| r.unknown()) {
+            let message = if report.stopped {
+                "The solver stopped before it knew if the map is colorable".to_string()
+            } else {
+                format!(
+                    "{} found no coloring, but that doesnt mean there is none. Try an exact solver like DSATUR",
+                    self.solved_with.name()
+                )
+            };
+            ui.vertical_centered(|ui| {
+                ui.colored_label(Color32::from_rgb(200, 120, 0), message);
+            });
+        }
+        if let Some(err) = &self.error {
+            ui.vertical_centered(|ui| {
+                ui.colored_label(Color32::RED, err);
+            });
+        }
+        if let Some(hint) = &self.hint {
+            ui.vertical_centered(|ui| {
+                ui.colored_label(Color32::from_rgb(0, 100, 200), hint);
+            });
+        }
+        if let Some(path) = &self.exported {
+            ui.vertical_centered(|ui| {
+                ui.colored_label(Color32::RED, format!("Exported to {}", path));
             });
         }
         if self.saved {
@@ -36,26 +157,185 @@ impl Scene for GenerateMap {
         }
         if self.iterations != 0 {
             ui.vertical_centered(|ui| {
-                ui.colored_label(Color32::RED, format!("Took {} iterations", self.iterations));
+                if self.solved_with == Strategy::Dfs {
+                    ui.colored_label(Color32::RED, format!("Took {} iterations", self.iterations));
+                } else {
+                    let backtracks = self.report.as_ref().map_or(0, |r| r.backtracks);
+                    ui.colored_label(
+                        Color32::RED,
+                        format!(
+                            "{} took {} nodes and {} backtracks",
+                            self.solved_with.name(),
+                            self.iterations,
+                            backtracks
+                        ),
+                    );
+                }
             });
         }
-
-        for chunk in self.map.0.iter().as_slice().chunks(10) {
+        if !self.comparison.is_empty() {
             ui.columns(3, |cols| {
-                cols[1].horizontal(|ui| {
-                    for cell in chunk {
-                        ui.group(|ui| {
-                            ui.colored_label(
-                                cell.clone().color(),
-                                RichText::new(cell.name.clone()).size(15.),
-                            );
+                egui::Grid::new("comparison").show(&mut cols[1], |ui| {
+                    for heading in ["Solver", "Colored", "Nodes", "Backtracks", "Time"] {
+                        ui.strong(heading);
+                    }
+                    ui.end_row();
+                    for (strategy, solution) in self.comparison.iter() {
+                        ui.label(strategy.name());
+                        ui.label(if solution.report.colored {
+                            "yes"
+                        } else if solution.report.unknown() {
+                            "unknown"
+                        } else {
+                            "no"
                         });
+                        ui.label(solution.report.iterations.to_string());
+                        ui.label(solution.report.backtracks.to_string());
+                        ui.label(format!("{:.2} ms", solution.elapsed.as_secs_f64() * 1000.));
+                        ui.end_row();
                     }
                 });
-            })
+            });
+        }
+        if let Some(chromatic) = &self.chromatic {
+            let clique: Vec<String> = chromatic
+                .clique
+                .iter()
+                .map(|c| self.map.0[*c].name.clone())
+                .collect();
+            ui.vertical_centered(|ui| {
+                ui.colored_label(
+                    Color32::RED,
+                    format!(
+                        "Chromatic number: {} (took {} iterations)",
+                        chromatic.colors, chromatic.iterations
+                    ),
+                );
+                // the clique proves the lower bound, anything above it was proven by the search
+                ui.label(format!(
+                    "At least {} colors are needed since {} all border each other",
+                    chromatic.clique.len(),
+                    clique.join(", ")
+                ));
+                if let Some(note) = &self.pin_note {
+                    ui.label(note);
+                }
+            });
+        }
+        if let Some(report) = &self.report {
+            // only list components if the map is split into several parts
+            if report.components.len() > 1 {
+                for (i, component) in report.components.iter().enumerate() {
+                    let names: Vec<String> = component
+                        .cells
+                        .iter()
+                        .map(|c| self.map.0[*c].name.clone())
+                        .collect();
+                    let status = if component.colored {
+                        format!("colored in {} iterations", component.iterations)
+                    } else if report.unknown() {
+                        "not colored".to_string()
+                    } else {
+                        "not colorable".to_string()
+                    };
+                    ui.vertical_centered(|ui| {
+                        ui.label(format!(
+                            "Component {} ({}): {}",
+                            i + 1,
+                            names.join(", "),
+                            status
+                        ));
+                    });
+                }
+            }
         }
 
-        ui.add_space(520. - ((self.map.0.len() / 10) as f32) * 33.);
+        ui.vertical_centered(|ui| {
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.show_graph, true, "Graph");
+                ui.selectable_value(&mut self.show_graph, false, "List");
+                if self.show_graph && ui.button("Reset view").clicked() {
+                    self.graph.reset_view();
+                }
+                if self.show_graph {
+                    ui.label("Right click a region to pin its color");
+                }
+            });
+        });
+        if let Some(replay) = &mut self.replay {
+            replay.update(ui);
+            let close = ui.vertical_centered(|ui| replay.controls(ui)).inner;
+            self.graph.focus = replay.current().map(|s| s.cell());
+            if close {
+                self.replay = None;
+                self.graph.focus = None;
+            }
+        }
+        // while replaying show the map the way the search has it
+        let shown = self.replay.as_ref().map_or(&self.map, |r| &r.map);
+        if self.show_graph {
+            // leave room for the settings and buttons below
+            let height = (ui.available_height() - 580.).max(200.);
+            let size = egui::Vec2::new(ui.available_width(), height);
+            let response = self.graph.show(ui, shown, size);
+            if self.job.is_none() && self.replay.is_none() {
+                self.pin_clicked(&response);
+            }
+            ui.add_space(10.);
+        } else {
+            for chunk in shown.0.iter().as_slice().chunks(10) {
+                ui.columns(3, |cols| {
+                    cols[1].horizontal(|ui| {
+                        for cell in chunk {
+                            let resp = ui.group(|ui| {
+                                ui.colored_label(
+                                    Color32::from(cell.color()),
+                                    RichText::new(cell.label.clone().unwrap_or(cell.name.clone()))
+                                        .size(15.),
+                                );
+                            });
+                            if let Some(description) = &cell.description {
+                                resp.response.on_hover_text(description);
+                            }
+                        }
+                    });
+                })
+            }
+            ui.add_space(520. - ((shown.0.len() / 10) as f32) * 33.);
+        }
+        ui.columns(3, |cols| {
+            cols[1].horizontal(|ui| {
+                ui.label(RichText::new("Name:").size(20.));
+                ui.text_edit_singleline(&mut self.header.name);
+            });
+            cols[1].horizontal(|ui| {
+                // pick how many colors may be used, and show them
+                ui.label(RichText::new("Colors:").size(20.));
+                ui.add(egui::DragValue::new(&mut self.k).clamp_range(1..=64));
+                for color in palette(self.k) {
+                    ui.colored_label(Color32::from(color), "■");
+                }
+                ui.add_space(20.);
+                // pick which solver to color the map with
+                ui.label(RichText::new("Time limit:").size(20.));
+                ui.add(
+                    egui::DragValue::new(&mut self.time_limit)
+                        .clamp_range(0.0..=600.0)
+                        .speed(0.5)
+                        .suffix(" s"),
+                )
+                .on_hover_text("How long a solver may take, 0 for as long as it needs");
+                ui.add_space(20.);
+                egui::ComboBox::from_label("Solver")
+                    .selected_text(self.strategy.name())
+                    .show_ui(ui, |ui| {
+                        for strategy in Strategy::ALL {
+                            ui.selectable_value(&mut self.strategy, strategy, strategy.name());
+                        }
+                    });
+            });
+        });
+        ui.add_space(10.);
         ui.vertical_centered(|ui| {
             let button = egui::Button::new(
                 RichText::new("Color the map")
@@ -63,15 +343,131 @@ impl Scene for GenerateMap {
                     .color(Color32::WHITE),
             )
             .fill(Color32::BLUE);
+            if let Some(job) = &self.job {
+                // something is still running, show how far it got
+                let mut status = match self.task {
+                    Task::Color => format!("{} is coloring the map", self.solved_with.name()),
+                    Task::Compare => "Comparing the solvers".to_string(),
+                    Task::Trace => "Recording the search".to_string(),
+                    Task::Chromatic => "Searching for the fewest colors".to_string(),
+                    Task::Blame => "Checking if the pins are to blame".to_string(),
+                };
+                if self.nodes > 0 {
+                    status += &format!(": {} nodes", self.nodes);
+                }
+                ui.label(format!(
+                    "{} in {:.1} s",
+                    status,
+                    job.started.elapsed().as_secs_f32()
+                ));
+                let button =
+                    egui::Button::new(RichText::new("Cancel").size(25.).color(Color32::WHITE))
+                        .fill(Color32::RED);
+                if ui.add(button).clicked() {
+                    job.cancel();
+                }
+            } else if ui.add(button).clicked() {
+                // Start the coloring from scratch, on another thread so the window keeps going
+                self.map.reset_colors();
+                self.blame = None;
+                self.solved_with = self.strategy;
+                self.nodes = 0;
+                self.report = None;
+                self.colorable = true;
+                self.iterations = 0;
+                self.chromatic = None;
+                self.hint = None;
+                self.replay = None;
+                self.graph.focus = None;
+                let (strategy, map, k) = (self.strategy, self.map.clone(), self.k);
+                self.start(Task::Color, self.limit(), move |control| {
+                    Outcome::Colored(strategy.solver().solve_with(&map, k, control))
+                });
+            }
+            ui.add_space(10.);
+            let button = egui::Button::new(
+                RichText::new("Compare solvers")
+                    .size(25.)
+                    .color(Color32::WHITE),
+            )
+            .fill(Color32::BLUE);
+            let resp = ui.add_enabled(self.job.is_none(), button);
+            if resp.clicked() {
+                // run every solver on an uncolored copy of the map
+                let mut blank = self.map.clone();
+                blank.reset_colors();
+                let (k, limit) = (self.k, self.limit());
+                self.start(Task::Compare, None, move |control| {
+                    let comparison = Strategy::ALL
+                        .iter()
+                        .map(|s| {
+                            // every solver gets the whole time limit, cancelling stops them all
+                            let control = control.clone().with_time_limit(limit);
+                            (*s, s.solver().solve_with(&blank, k, &control))
+                        })
+                        .collect();
+                    Outcome::Compared(comparison)
+                });
+            }
+            ui.add_space(10.);
+            let button = egui::Button::new(
+                RichText::new("Watch the search")
+                    .size(25.)
+                    .color(Color32::WHITE),
+            )
+            .fill(Color32::BLUE);
+            let resp = ui.add_enabled(self.job.is_none(), button);
+            if resp.clicked() {
+                // record the DFS on a blank copy, to play it back once its done
+                let (map, k) = (self.map.clone(), self.k);
+                self.replay = None;
+                self.start(Task::Trace, self.limit(), move |control| {
+                    Outcome::Traced(map.trace_with(k, 100_000, control).1)
+                });
+            }
+            ui.add_space(10.);
+            let button = egui::Button::new(
+                RichText::new("Find chromatic number")
+                    .size(25.)
+                    .color(Color32::WHITE),
+            )
+            .fill(Color32::BLUE);
+            let resp = ui.add_enabled(self.job.is_none(), button);
+            if resp.clicked() {
+                // search for the fewest colors, the witness coloring is shown once its done
+                let map = self.map.clone();
+                self.start(Task::Chromatic, self.limit(), move |control| {
+                    let found = map.chromatic_number_with(control).and_then(|chromatic| {
+                        if map.0.iter().all(|c| c.pinned.is_none()) {
+                            return Some((chromatic, None));
+                        }
+                        // the chromatic number ignores the pins, so look again from there
+                        let fewest = pin::fewest_colors(&map, chromatic.colors, control);
+                        (!control.stopped()).then_some((chromatic, fewest))
+                    });
+                    Outcome::Chromatic(found)
+                });
+            }
+            ui.add_space(10.);
+            let button = egui::Button::new(RichText::new("Hint").size(25.).color(Color32::WHITE))
+                .fill(Color32::BLUE);
             let resp = ui.add(button);
             if resp.clicked() {
-                // Start the coloring
-                let t = self.map.color_map();
-                if !t.0 {
-                    self.colorable = false;
-                } else {
-                    self.iterations = t.1
-                }
+                // explain the next step for the colors the map has right now
+                self.hint = Some(hint(&self.map, self.k).explain(&self.map, self.k));
+            }
+            ui.add_space(10.);
+            let button = egui::Button::new(
+                RichText::new("Color it yourself")
+                    .size(25.)
+                    .color(Color32::WHITE),
+            )
+            .fill(Color32::from_rgb(0, 150, 0));
+            let resp = ui.add(button);
+            if resp.clicked() {
+                let mut next_scene = Puzzle::from(self.map.clone());
+                next_scene.set_colors(self.k);
+                *self.next = Some(SceneType::Puzzle(next_scene))
             }
             ui.add_space(10.);
             let button =
@@ -79,21 +475,36 @@ impl Scene for GenerateMap {
                     .fill(Color32::RED);
             let resp = ui.add(button);
             if resp.clicked() {
-                self.next = Box::new(Some(SceneType::Start(StartingScene::new())))
+                *self.next = Some(SceneType::Start(StartingScene::new()))
             }
             let button =
                 egui::Button::new(RichText::new("Save Map").size(25.).color(Color32::WHITE))
                     .fill(Color32::from_rgb(255, 0, 255));
             let resp = ui.add(button);
             if resp.clicked() {
-                let mut file = File::create(get_next_file_path()).unwrap();
-                file.write_all(
-                    toml::to_string::<HashMap<String, Vec<String>>>(&self.map.clone().into())
-                        .expect("Cannot convert")
-                        .as_bytes(),
-                )
-                .unwrap();
-                self.saved = true;
+                // save the colors too, so a solved map loads as solved
+                let path = get_next_file_path("toml");
+                match format::save(Path::new(&path), &self.map, self.header.clone()) {
+                    Ok(()) => {
+                        self.saved = true;
+                        self.error = None;
+                    }
+                    Err(err) => self.error = Some(format!("Could not save the map: {}", err)),
+                }
+            }
+            let button =
+                egui::Button::new(RichText::new("Export DOT").size(25.).color(Color32::WHITE))
+                    .fill(Color32::DARK_GRAY);
+            let resp = ui.add(button);
+            if resp.clicked() {
+                self.export("dot");
+            }
+            let button =
+                egui::Button::new(RichText::new("Export SVG").size(25.).color(Color32::WHITE))
+                    .fill(Color32::DARK_GRAY);
+            let resp = ui.add(button);
+            if resp.clicked() {
+                self.export("svg");
             }
         });
     }
@@ -110,6 +521,180 @@ impl From<Map> for GenerateMap {
             colorable: true,
             saved: false,
             iterations: 0,
+            report: None,
+            k: 4,
+            chromatic: None,
+            strategy: Strategy::Dfs,
+            solved_with: Strategy::Dfs,
+            comparison: Vec::new(),
+            warnings: Vec::new(),
+            header: Header::default(),
+            error: None,
+            exported: None,
+            show_graph: true,
+            graph: GraphView::default(),
+            hint: None,
+            replay: None,
+            job: None,
+            task: Task::Color,
+            nodes: 0,
+            time_limit: 0.,
+            blame: None,
+            pin_note: None,
+        }
+    }
+}
+
+impl GenerateMap {
+    fn pin_clicked(&mut self, response: &egui::Response) {
+        // right clicking a region pins it to the next color, and after the
+        // last color unpins it again
+        if !response.clicked_by(egui::PointerButton::Secondary) {
+            return;
+        }
+        let i = match response
+            .interact_pointer_pos()
+            .and_then(|p| self.graph.cell_at(response, p))
+        {
+            Some(i) => i,
+            None => return,
+        };
+        let cell = &mut self.map.0[i];
+        cell.pinned = match cell.pinned {
+            None => Some(0),
+            Some(c) if c + 1 < self.k => Some(c + 1),
+            Some(_) => None,
+        };
+        cell.color = cell.pinned;
+        self.saved = false;
+    }
+    fn limit(&self) -> Option<Duration> {
+        (self.time_limit > 0.).then(|| Duration::from_secs_f32(self.time_limit))
+    }
+    fn start<F>(&mut self, task: Task, limit: Option<Duration>, work: F)
+    where
+        F: FnOnce(&Control) -> Outcome + Send + 'static,
+    {
+        // run the work on another thread so the window keeps going, poll picks up the outcome
+        self.task = task;
+        self.nodes = 0;
+        self.error = None;
+        self.job = Some(Rc::new(background::run(limit, work)));
+    }
+    fn poll(&mut self, ui: &Ui) {
+        // pick up what the background job sent since the last frame
+        let job = match &self.job {
+            Some(job) => job.clone(),
+            None => return,
+        };
+        for progress in job.progress() {
+            self.nodes = progress.nodes;
+            // show the best partial coloring so far, when coloring the map itself
+            if let (Task::Color, Some(colors)) = (self.task, progress.colors) {
+                for (cell, color) in self.map.0.iter_mut().zip(colors) {
+                    cell.color = color;
+                }
+            }
+        }
+        let outcome = match job.result() {
+            Some(outcome) => outcome,
+            None => {
+                ui.ctx().request_repaint();
+                return;
+            }
+        };
+        self.job = None;
+        match outcome {
+            Outcome::Colored(solution) => self.show_solution(solution),
+            Outcome::Compared(comparison) => self.comparison = comparison,
+            Outcome::Traced(trace) => self.replay = Some(Replay::new(&self.map, trace)),
+            Outcome::Chromatic(Some((chromatic, fewest))) => self.show_chromatic(chromatic, fewest),
+            Outcome::Chromatic(None) => {
+                self.error =
+                    Some("The search for the fewest colors stopped before it was done".to_string())
+            }
+            Outcome::Blamed(blame) => self.blame = blame,
+        }
+    }
+    fn show_solution(&mut self, solution: Solution) {
+        self.map.clear_colors();
+        solution.apply(&mut self.map);
+        let t = solution.report;
+        self.colorable = t.colored || t.unknown();
+        self.iterations = if t.colored { t.iterations } else { 0 };
+        self.header.k = Some(self.k);
+        self.header.solver = Some(self.solved_with.name().to_string());
+        self.report = Some(t);
+        if !self.colorable && self.map.0.iter().any(|c| c.pinned.is_some()) {
+            // find out if the pins are why, which can take a while too
+            let (map, k) = (self.map.clone(), self.k);
+            self.start(Task::Blame, Some(Duration::from_secs(2)), move |control| {
+                Outcome::Blamed(pin::blame(&map, k, control))
+            });
+        }
+    }
+    fn show_chromatic(&mut self, chromatic: Chromatic, fewest: Option<(usize, Solution)>) {
+        // color the map with the fewest colors. with pins, fewest is the coloring
+        // that keeps them, or None if they clash
+        self.pin_note = None;
+        if self.map.0.iter().all(|c| c.pinned.is_none()) {
+            for (cell, color) in self.map.0.iter_mut().zip(chromatic.coloring.iter()) {
+                cell.color = Some(*color);
+            }
+            self.k = chromatic.colors.max(1);
+        } else if let Some((k, solution)) = fewest {
+            self.map.clear_colors();
+            solution.apply(&mut self.map);
+            self.k = k;
+            if k > chromatic.colors {
+                self.pin_note = Some(format!("Keeping the pinned colors needs {} colors", k));
+            }
+        } else {
+            // the pins clash, leave the colors as they are
+            self.pin_note = pin::problems(&self.map, usize::MAX).first().map(|p| {
+                format!(
+                    "No coloring keeps the pins: {}",
+                    p.explain(&self.map, self.k)
+                )
+            });
+        }
+        self.header.k = Some(self.k);
+        self.header.solver = Some("Chromatic number search".to_string());
+        self.colorable = true;
+        self.report = None;
+        self.chromatic = Some(chromatic);
+        self.hint = None;
+        self.replay = None;
+        self.graph.focus = None;
+    }
+    pub fn set_colors(&mut self, k: usize) {
+        self.k = k;
+    }
+    pub fn set_warnings(&mut self, warnings: Vec<String>) {
+        self.warnings = warnings;
+    }
+    fn export(&mut self, extension: &str) {
+        // write the map next to the saved maps, in the format of the extension
+        let path = get_next_file_path(extension);
+        match format::save(Path::new(&path), &self.map, self.header.clone()) {
+            Ok(()) => {
+                self.exported = Some(path);
+                self.error = None;
+            }
+            Err(err) => self.error = Some(format!("Could not export the map: {}", err)),
+        }
+    }
+    pub fn set_header(&mut self, header: Header) {
+        // pick up how the map was colored when it was saved
+        if let Some(k) = header.k {
+            self.k = k;
+        }
+        if let Some(strategy) = Strategy::ALL
+            .into_iter()
+            .find(|s| Some(s.name()) == header.solver.as_deref())
+        {
+            self.strategy = strategy;
         }
+        self.header = header;
     }
 }
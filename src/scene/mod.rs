@@ -1,13 +1,14 @@
 use egui::Ui;
 
 use self::{
-    create_links::CreateLinks, enter_names::EnterNames, generate_map::GenerateMap,
+    create_links::CreateLinks, enter_names::EnterNames, generate_map::GenerateMap, puzzle::Puzzle,
     start::StartingScene,
 };
 
 pub mod create_links;
 pub mod enter_names;
 pub mod generate_map;
+pub mod puzzle;
 pub mod start;
 
 pub trait Scene: Clone {
@@ -20,12 +21,15 @@ pub trait Scene: Clone {
     }
 }
 
+// there is only ever one scene around, so its size doesnt matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Clone)]
 pub enum SceneType {
     Start(StartingScene),
     EnterNames(EnterNames),
     CreateLinks(CreateLinks),
     GenerateMap(GenerateMap),
+    Puzzle(Puzzle),
 }
 
 impl Scene for SceneType {
@@ -35,6 +39,7 @@ impl Scene for SceneType {
             SceneType::EnterNames(t) => t.draw(ui),
             SceneType::CreateLinks(t) => t.draw(ui),
             SceneType::GenerateMap(t) => t.draw(ui),
+            SceneType::Puzzle(t) => t.draw(ui),
         }
     }
     fn new() -> SceneType {
@@ -46,6 +51,7 @@ impl Scene for SceneType {
             SceneType::EnterNames(t) => t.next_scene(),
             SceneType::CreateLinks(t) => t.next_scene(),
             SceneType::GenerateMap(t) => t.next_scene(),
+            SceneType::Puzzle(t) => t.next_scene(),
         }
     }
 }
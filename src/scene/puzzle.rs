@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use egui::{Color32, PointerButton, RichText, Ui, Vec2};
+use map_coloring::{cell::Map, hint::hint, palette::palette};
+
+use crate::utility::graph_view::GraphView;
+
+use super::{generate_map::GenerateMap, Scene, SceneType};
+
+#[derive(Clone)]
+pub struct Puzzle {
+    map: Map,
+    next: Box<Option<SceneType>>,
+    k: usize,                 // the number of colors to cycle through
+    moves: usize,             // how many times a region was recolored
+    started: Instant,         // when the current game started
+    solved: Option<Duration>, // how long it took, once the map is colored
+    graph: GraphView,
+    hint: Option<String>, // the explanation of the last hint
+}
+
+impl Scene for Puzzle {
+    fn new() -> Puzzle {
+        Puzzle {
+            map: Map::default(),
+            next: Box::new(None),
+            k: 4,
+            moves: 0,
+            started: Instant::now(),
+            solved: None,
+            graph: GraphView::default(),
+            hint: None,
+        }
+    }
+    fn draw(&mut self, ui: &mut Ui) {
+        ui.vertical_centered(|ui| {
+            ui.label(
+                "Click a region to give it the next color, right click for the previous one. \
+                Neighbors with the same color are marked red.",
+            );
+            ui.horizontal(|ui| {
+                for (i, color) in palette(self.k).into_iter().enumerate() {
+                    let used = self.map.0.iter().filter(|c| c.color == Some(i)).count();
+                    ui.colored_label(Color32::from(color), format!("■ {}", used));
+                }
+            });
+        });
+        let size = Vec2::new(ui.available_width(), 480.);
+        let response = self.graph.show(ui, &self.map, size);
+        // once its solved the map stays as it is
+        if self.solved.is_none() {
+            let clicked = response.interact_pointer_pos().and_then(|p| {
+                if response.clicked() {
+                    self.graph.cell_at(&response, p).map(|i| (i, 1))
+                } else if response.clicked_by(PointerButton::Secondary) {
+                    self.graph.cell_at(&response, p).map(|i| (i, self.k))
+                } else {
+                    None
+                }
+            });
+            // pinned regions cant be changed
+            let clicked = clicked.filter(|(i, _)| self.map.0[*i].pinned.is_none());
+            if let Some((i, step)) = clicked {
+                // cycle through no color, then every color in order
+                let next = self.map.0[i].color.map_or(0, |c| c + 1) % (self.k + 1);
+                let next = (next + step - 1) % (self.k + 1);
+                self.map.0[i].color = if next == self.k { None } else { Some(next) };
+                self.moves += 1;
+                self.hint = None;
+                if (0..self.map.0.len()).all(|i| self.map.is_valid_at(i)) {
+                    self.solved = Some(self.started.elapsed());
+                }
+            }
+        }
+
+        let conflicts = self.map.conflicts().len();
+        let left = self.map.0.iter().filter(|c| c.color.is_none()).count();
+        let elapsed = self.solved.unwrap_or_else(|| self.started.elapsed());
+        ui.add_space(10.);
+        ui.vertical_centered(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "Time {}:{:02}   Moves {}",
+                    elapsed.as_secs() / 60,
+                    elapsed.as_secs() % 60,
+                    self.moves
+                ))
+                .size(20.),
+            );
+            match self.solved {
+                Some(_) => {
+                    ui.colored_label(
+                        Color32::from_rgb(0, 150, 0),
+                        RichText::new(format!("Solved with {} colors!", self.k)).size(25.),
+                    );
+                }
+                None => {
+                    ui.label(format!(
+                        "{} regions left to color, {} conflicts",
+                        left, conflicts
+                    ));
+                    // keep the timer ticking
+                    ui.ctx().request_repaint();
+                }
+            }
+            if let Some(hint) = &self.hint {
+                ui.colored_label(Color32::from_rgb(0, 100, 200), hint);
+            }
+            ui.add_space(10.);
+            if self.solved.is_none() {
+                let button =
+                    egui::Button::new(RichText::new("Hint").size(25.).color(Color32::WHITE))
+                        .fill(Color32::from_rgb(0, 100, 200));
+                if ui.add(button).clicked() {
+                    self.hint = Some(hint(&self.map, self.k).explain(&self.map, self.k));
+                }
+                ui.add_space(10.);
+            }
+            let button =
+                egui::Button::new(RichText::new("Restart").size(25.).color(Color32::WHITE))
+                    .fill(Color32::BLUE);
+            if ui.add(button).clicked() {
+                self.map.reset_colors();
+                self.moves = 0;
+                self.started = Instant::now();
+                self.solved = None;
+                self.hint = None;
+            }
+            ui.add_space(10.);
+            let button =
+                egui::Button::new(RichText::new("Go back").size(25.).color(Color32::WHITE))
+                    .fill(Color32::RED);
+            if ui.add(button).clicked() {
+                let mut next_scene = GenerateMap::from(self.map.clone());
+                next_scene.set_colors(self.k);
+                *self.next = Some(SceneType::GenerateMap(next_scene))
+            }
+        });
+    }
+    fn next_scene(&self) -> &Option<SceneType> {
+        self.next.as_ref()
+    }
+}
+
+impl From<Map> for Puzzle {
+    fn from(mut map: Map) -> Puzzle {
+        // the puzzle starts from a blank map, the pinned regions are given
+        map.reset_colors();
+        Puzzle {
+            map,
+            ..Puzzle::new()
+        }
+    }
+}
+
+impl Puzzle {
+    pub fn set_colors(&mut self, k: usize) {
+        self.k = k.max(1);
+    }
+}
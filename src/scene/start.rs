@@ -3,19 +3,27 @@ use std::process;
 use egui::{Color32, RichText, Ui};
 use glob::glob;
 
-use crate::cell::Map;
+use map_coloring::format::{self, Format};
 
 use super::{enter_names::EnterNames, generate_map::GenerateMap, Scene, SceneType};
 
 #[derive(Clone)]
-pub struct StartingScene(pub Box<Option<SceneType>>);
+pub struct StartingScene {
+    next: Box<Option<SceneType>>,
+    error: Option<String>, // the last loading error
+    repair: bool,          // fix link problems when loading instead of failing
+}
 
 impl Scene for StartingScene {
     fn new() -> Self
     where
         Self: Sized,
     {
-        StartingScene(Box::new(None))
+        StartingScene {
+            next: Box::new(None),
+            error: None,
+            repair: false,
+        }
     }
     fn draw(&mut self, ui: &mut Ui) {
         ui.vertical_centered(|ui| {
@@ -24,7 +32,7 @@ impl Scene for StartingScene {
                     .fill(Color32::BLUE);
             let resp = ui.add(button);
             if resp.clicked() {
-                self.0 = Box::new(Some(SceneType::EnterNames(EnterNames::new())))
+                *self.next = Some(SceneType::EnterNames(EnterNames::new()))
             }
         });
         ui.vertical_centered(|ui| {
@@ -33,19 +41,49 @@ impl Scene for StartingScene {
                     .size(50.)
                     .color(Color32::WHITE),
                 |ui| {
-                    for p in glob("./maps/*.toml").unwrap().filter_map(|t| t.ok()) {
+                    // every file in a format that can be loaded
+                    for p in glob("./maps/*.*")
+                        .unwrap()
+                        .filter_map(|t| t.ok())
+                        .filter(|p| Format::from_path(p).is_some_and(|f| f.can_read()))
+                    {
                         if ui
                             .button(p.file_name().unwrap().to_str().unwrap())
                             .clicked()
                         {
-                            self.0 = Box::new(Some(SceneType::GenerateMap(GenerateMap::from(
-                                Map::from_file(&p),
-                            ))))
+                            match format::load(&p, self.repair) {
+                                Ok(loaded) => {
+                                    let mut next_scene = GenerateMap::from(loaded.map);
+                                    next_scene.set_header(loaded.header);
+                                    next_scene.set_warnings(
+                                        loaded
+                                            .report
+                                            .issues
+                                            .iter()
+                                            .map(|i| i.to_string())
+                                            .collect(),
+                                    );
+                                    *self.next = Some(SceneType::GenerateMap(next_scene))
+                                }
+                                // keep the app running and tell the user what went wrong
+                                Err(err) => {
+                                    self.error = Some(format!("{}: {}", p.display(), err));
+                                    ui.close_menu();
+                                }
+                            }
                         }
                     }
                 },
             );
         });
+        ui.vertical_centered(|ui| {
+            ui.checkbox(&mut self.repair, "Repair one-sided links when loading");
+        });
+        if let Some(err) = &self.error {
+            ui.vertical_centered(|ui| {
+                ui.colored_label(Color32::RED, err);
+            });
+        }
         // QUIT BUTTON
         ui.vertical_centered(|ui| {
             let button = egui::Button::new(RichText::new("Quit").size(50.).color(Color32::WHITE))
@@ -57,6 +95,6 @@ impl Scene for StartingScene {
         });
     }
     fn next_scene(&self) -> &Option<SceneType> {
-        self.0.as_ref()
+        self.next.as_ref()
     }
 }
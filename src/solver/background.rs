@@ -0,0 +1,122 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::control::{Control, Progress};
+
+// the searches recurse once per cell, so big maps need a much deeper
+// stack than the 2 MiB threads get by default
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+pub struct Job<T> {
+    cancel: Arc<AtomicBool>,
+    progress: Receiver<Progress>,
+    result: Receiver<T>,
+    pub started: Instant,
+}
+
+pub fn run<T, F>(limit: Option<Duration>, work: F) -> Job<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&Control) -> T + Send + 'static,
+{
+    // do the work on its own thread, so whoever started it can keep going
+    // and watch the progress, or cancel it. the work should stop when the
+    // control it gets says so
+    let (progress, progress_receiver) = channel();
+    let (result, result_receiver) = channel();
+    let control = Control::default()
+        .with_time_limit(limit)
+        .with_progress(progress);
+    let cancel = control.cancel_flag();
+    thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || {
+            // nobody might be waiting anymore, which is fine
+            let _ = result.send(work(&control));
+        })
+        .expect("couldnt start a thread for the job");
+    Job {
+        cancel,
+        progress: progress_receiver,
+        result: result_receiver,
+        started: Instant::now(),
+    }
+}
+
+impl<T> Job<T> {
+    pub fn cancel(&self) {
+        // the work stops the next time it checks, and sends what it has
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+    pub fn progress(&self) -> Vec<Progress> {
+        // everything the solver sent since the last call
+        self.progress.try_iter().collect()
+    }
+    pub fn result(&self) -> Option<T> {
+        // the result, once the work is done
+        self.result.try_recv().ok()
+    }
+}
+
+impl<T> Drop for Job<T> {
+    fn drop(&mut self) {
+        // nobody can get the result anymore, so dont keep the thread busy
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::complete;
+
+    fn wait<T>(job: &Job<T>) -> T {
+        // wait for the result, failing the test if it never comes
+        for _ in 0..6000 {
+            if let Some(result) = job.result() {
+                return result;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("the job didnt finish");
+    }
+
+    #[test]
+    fn jobs_stop_when_told() {
+        let map = complete(30);
+        // no time at all stops the chromatic number search right away
+        let copy = map.clone();
+        let job = run(Some(Duration::ZERO), move |control| {
+            copy.chromatic_number_with(control)
+        });
+        assert!(wait(&job).is_none());
+        // and so does cancelling
+        let job = run(None, move |control| {
+            while !control.stopped() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            map.trace_with(4, 1000, control).0
+        });
+        job.cancel();
+        assert!(wait(&job).stopped);
+    }
+
+    #[test]
+    fn deep_searches_fit_on_the_stack() {
+        // DSATUR recurses once per cell, so this grid overflows a default stack
+        let map = crate::bench::grid(120, 120);
+        let job = run(None, move |control| {
+            crate::solver::Strategy::Dsatur
+                .solver()
+                .solve_with(&map, 4, control)
+        });
+        assert!(wait(&job).report.colored);
+    }
+}
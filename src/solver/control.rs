@@ -0,0 +1,132 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+const PROGRESS_EVERY: Duration = Duration::from_millis(100); // how often progress is sent
+const CHECK_EVERY: usize = 1024; // how many nodes go by between looking at the clock
+
+#[derive(Clone, Debug)]
+pub struct Progress {
+    pub nodes: usize, // how many nodes the search went through so far
+    pub colors: Option<Vec<Option<usize>>>, // the best partial coloring, if it got better
+}
+
+#[derive(Clone)]
+pub struct Control {
+    cancel: Arc<AtomicBool>,   // set from another thread to stop the search
+    deadline: Option<Instant>, // when the search has to give up
+    progress: Option<Sender<Progress>>,
+    last: Cell<Instant>, // when progress was last sent
+    best: Cell<usize>,   // the most cells that were colored in any progress sent
+    stopped: Cell<bool>, // set once stopped is true, so later checks are cheap
+}
+
+impl Default for Control {
+    fn default() -> Control {
+        Control {
+            cancel: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+            progress: None,
+            last: Cell::new(Instant::now()),
+            best: Cell::new(0),
+            stopped: Cell::new(false),
+        }
+    }
+}
+
+impl Control {
+    pub fn with_time_limit(mut self, limit: Option<Duration>) -> Control {
+        // give up once the limit has passed, None to search as long as it takes.
+        // a limit too far off to be a point in time is the same as none
+        self.deadline = limit.and_then(|l| Instant::now().checked_add(l));
+        self
+    }
+    pub fn with_progress(mut self, progress: Sender<Progress>) -> Control {
+        self.progress = Some(progress);
+        self
+    }
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        // the flag to set to cancel the search, from any thread
+        self.cancel.clone()
+    }
+    pub fn stopped(&self) -> bool {
+        // true once the search was cancelled or ran out of time
+        if !self.stopped.get() {
+            self.stopped.set(
+                self.cancel.load(Ordering::Relaxed)
+                    || self.deadline.is_some_and(|d| Instant::now() >= d),
+            );
+        }
+        self.stopped.get()
+    }
+    pub fn poll<F>(&self, nodes: usize, colors: F) -> bool
+    where
+        F: FnOnce() -> Vec<Option<usize>>,
+    {
+        // for searches that call this at every node: true once they should stop.
+        // looking at the clock every node would slow the search down, so that
+        // and sending progress only happens every CHECK_EVERY nodes
+        if self.stopped.get() {
+            return true;
+        }
+        if nodes.is_multiple_of(CHECK_EVERY) {
+            if self.stopped() {
+                return true;
+            }
+            self.progress(nodes, colors);
+        }
+        false
+    }
+    pub fn progress<F>(&self, nodes: usize, colors: F)
+    where
+        F: FnOnce() -> Vec<Option<usize>>,
+    {
+        // send how far the search got, at most every PROGRESS_EVERY.
+        // colors is only called when its time to send, since copying them is slow
+        let sender = match &self.progress {
+            Some(sender) if self.last.get().elapsed() >= PROGRESS_EVERY => sender,
+            _ => return,
+        };
+        self.last.set(Instant::now());
+        let colors = colors();
+        let colored = colors.iter().filter(|c| c.is_some()).count();
+        let better = colored > self.best.get();
+        if better {
+            self.best.set(colored);
+        }
+        // the receiver may be gone if nobody waits for the result anymore
+        let _ = sender.send(Progress {
+            nodes,
+            colors: better.then_some(colors),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_time_limits_mean_no_limit() {
+        let control = Control::default().with_time_limit(Some(Duration::MAX));
+        assert!(!control.stopped());
+        let control = Control::default().with_time_limit(Some(Duration::ZERO));
+        assert!(control.stopped());
+    }
+
+    #[test]
+    fn polling_only_looks_every_so_often() {
+        let control = Control::default();
+        control.cancel_flag().store(true, Ordering::Relaxed);
+        assert!(!control.poll(1, Vec::new));
+        assert!(control.poll(CHECK_EVERY, Vec::new));
+        // once it said stop, it keeps saying so
+        assert!(control.poll(CHECK_EVERY + 1, Vec::new));
+    }
+}
@@ -0,0 +1,27 @@
+use std::time::Instant;
+
+use crate::{cell::Map, trace::Trace};
+
+use super::{control::Control, Solution, Solver};
+
+pub struct Dfs; // the original search in Cell::color_in
+
+impl Solver for Dfs {
+    fn name(&self) -> &'static str {
+        "DFS"
+    }
+    fn exact(&self) -> bool {
+        false
+    }
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution {
+        // color_map works in place, so run it on a copy
+        let start = Instant::now();
+        let mut map = map.clone();
+        let report = map.color_map_with(k, &mut Trace::off(), control);
+        Solution {
+            colors: map.0.iter().map(|c| c.color).collect(),
+            report,
+            elapsed: start.elapsed(),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use crate::cell::Map;
+
+use super::{by_component, control::Control, Solution, Solver};
+
+pub struct Dsatur; // backtracking search that colors the most constrained cell first
+
+impl Solver for Dsatur {
+    fn name(&self) -> &'static str {
+        "DSATUR"
+    }
+    fn exact(&self) -> bool {
+        true
+    }
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution {
+        by_component(map, k, self.exact(), control, |cells, colors| {
+            color(map, cells, k, colors, control)
+        })
+    }
+}
+
+struct Search<'a> {
+    map: &'a Map,
+    cells: &'a [usize],         // the cells that still take part in the search
+    k: usize,                   // the number of colors
+    colors: Vec<Option<usize>>, // the color of every cell in the map
+    counts: Vec<usize>,         // counts[i * k + c] is how many neighbors of i have color c
+    saturation: Vec<usize>,     // the number of different colors around every cell
+    free: Vec<usize>,           // the number of uncolored neighbors of every cell
+    nodes: usize,               // how many colors were tried
+    backtracks: usize,          // how many colors had to be taken back
+    control: &'a Control,
+}
+
+pub fn color(
+    map: &Map,
+    cells: &[usize],
+    k: usize,
+    colors: &mut [Option<usize>],
+    control: &Control,
+) -> (bool, usize, usize) {
+    // color the given cells (usually one component) with k colors using DSATUR.
+    // returns whether it worked, and how many nodes and backtracks it took.
+    let n = map.0.len();
+    let mut search = Search {
+        map,
+        cells,
+        k,
+        // start from all the colors so far, so progress shows the whole map
+        colors: colors.to_vec(),
+        counts: vec![0; n * k],
+        saturation: vec![0; n],
+        free: map.0.iter().map(|c| c.connections.len()).collect(),
+        nodes: 0,
+        backtracks: 0,
+        control,
+    };
+    // cells that are already colored stay the way they are
+    let mut used = 0;
+    for i in cells.iter().copied() {
+        if let Some(c) = colors[i] {
+            if c >= k {
+                return (false, 0, 0);
+            }
+            search.assign(i, c);
+            used = used.max(c + 1);
+        }
+    }
+    let res = search.step(used);
+    if res {
+        for i in cells {
+            colors[*i] = search.colors[*i];
+        }
+    }
+    (res, search.nodes, search.backtracks)
+}
+
+impl<'a> Search<'a> {
+    fn step(&mut self, used: usize) -> bool {
+        // pick the next cell, or finish if every cell is colored
+        let i = match self.pick() {
+            Some(i) => i,
+            None => return true,
+        };
+        // colors are interchangeable, so only one unused color needs trying
+        for c in 0..self.k.min(used + 1) {
+            if self.counts[i * self.k + c] != 0 {
+                continue;
+            }
+            self.nodes += 1;
+            if self.control.poll(self.nodes, || self.colors.clone()) {
+                return false;
+            }
+            self.assign(i, c);
+            if self.step(used.max(c + 1)) {
+                return true;
+            }
+            self.unassign(i, c);
+            self.backtracks += 1;
+        }
+        false
+    }
+    fn pick(&self) -> Option<usize> {
+        // the uncolored cell with the highest saturation, ties broken by
+        // the number of uncolored neighbors and then by position
+        let mut best: Option<(usize, usize, usize)> = None;
+        for i in self.cells {
+            if self.colors[*i].is_some() {
+                continue;
+            }
+            let key = (self.saturation[*i], self.free[*i], *i);
+            match best {
+                Some((s, d, _)) if (s, d) >= (key.0, key.1) => {}
+                _ => best = Some(key),
+            }
+        }
+        best.map(|(_, _, i)| i)
+    }
+    fn assign(&mut self, i: usize, c: usize) {
+        self.colors[i] = Some(c);
+        for n in &self.map.0[i].connections {
+            self.free[*n] -= 1;
+            let count = &mut self.counts[n * self.k + c];
+            *count += 1;
+            if *count == 1 {
+                // the first neighbor with this color makes the cell more saturated
+                self.saturation[*n] += 1;
+            }
+        }
+    }
+    fn unassign(&mut self, i: usize, c: usize) {
+        self.colors[i] = None;
+        for n in &self.map.0[i].connections {
+            self.free[*n] += 1;
+            let count = &mut self.counts[n * self.k + c];
+            *count -= 1;
+            if *count == 0 {
+                self.saturation[*n] -= 1;
+            }
+        }
+    }
+}
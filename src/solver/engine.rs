@@ -0,0 +1,229 @@
+use crate::cell::Map;
+
+use super::{by_component, control::Control, dsatur::Dsatur, Solution, Solver};
+
+pub const MAX_COLORS: usize = 64; // domains are stored as one u64 per cell
+
+pub struct Engine; // an iterative DSATUR-style search that never recurses or allocates while searching
+
+impl Solver for Engine {
+    fn name(&self) -> &'static str {
+        "Iterative engine"
+    }
+    fn exact(&self) -> bool {
+        true
+    }
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution {
+        if k > MAX_COLORS {
+            // the domains dont have room for that many colors, DSATUR does the same search without them
+            return Dsatur.solve_with(map, k, control);
+        }
+        let mut state = State::new(map, k);
+        by_component(map, k, self.exact(), control, |cells, colors| {
+            state.color(cells, colors, control)
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Frame {
+    cell: u32,    // the cell this frame is coloring
+    options: u64, // the colors that are still left to try
+    color: u32,   // the color that is currently assigned (NONE if none)
+    used: u32,    // how many different colors were in use before this frame
+}
+
+const NONE: u32 = u32::MAX;
+
+pub struct State {
+    k: usize,
+    offsets: Vec<u32>, // the neighbors of cell i are adjacent[offsets[i]..offsets[i + 1]]
+    adjacent: Vec<u32>,
+    colors: Vec<u32>,  // the color of every cell, or NONE
+    counts: Vec<u16>,  // counts[i * k + c] is how many neighbors of i have color c
+    domains: Vec<u64>, // bit c is set if no neighbor of i has color c
+    stack: Vec<Frame>, // the explicit stack that replaces recursion
+    nodes: usize,
+    backtracks: usize,
+}
+
+impl State {
+    pub fn new(map: &Map, k: usize) -> State {
+        // lay the map out in flat arrays, this is the only place that allocates
+        assert!(
+            k <= MAX_COLORS,
+            "the engine can use at most {} colors",
+            MAX_COLORS
+        );
+        let n = map.0.len();
+        let mut offsets = Vec::with_capacity(n + 1);
+        let mut adjacent = Vec::new();
+        offsets.push(0);
+        for (i, cell) in map.0.iter().enumerate() {
+            let start = adjacent.len();
+            for c in cell.connections.iter() {
+                // skip self loops and links that were listed twice
+                if *c != i && !adjacent[start..].contains(&(*c as u32)) {
+                    adjacent.push(*c as u32);
+                }
+            }
+            offsets.push(adjacent.len() as u32);
+        }
+        let full = if k == MAX_COLORS {
+            u64::MAX
+        } else {
+            (1 << k) - 1
+        };
+        State {
+            k,
+            offsets,
+            adjacent,
+            colors: vec![NONE; n],
+            counts: vec![0; n * k],
+            domains: vec![full; n],
+            stack: Vec::with_capacity(n),
+            nodes: 0,
+            backtracks: 0,
+        }
+    }
+    fn neighbors(&self, i: usize) -> std::ops::Range<usize> {
+        self.offsets[i] as usize..self.offsets[i + 1] as usize
+    }
+    fn assign(&mut self, i: usize, c: usize) {
+        self.colors[i] = c as u32;
+        for j in self.neighbors(i) {
+            let n = self.adjacent[j] as usize;
+            self.counts[n * self.k + c] += 1;
+            self.domains[n] &= !(1 << c);
+        }
+    }
+    fn unassign(&mut self, i: usize, c: usize) {
+        self.colors[i] = NONE;
+        for j in self.neighbors(i) {
+            let n = self.adjacent[j] as usize;
+            let count = &mut self.counts[n * self.k + c];
+            *count -= 1;
+            if *count == 0 {
+                self.domains[n] |= 1 << c;
+            }
+        }
+    }
+    fn wiped_out(&self, i: usize) -> bool {
+        // true if coloring i left one of its uncolored neighbors without colors
+        self.neighbors(i).any(|j| {
+            let n = self.adjacent[j] as usize;
+            self.colors[n] == NONE && self.domains[n] == 0
+        })
+    }
+    fn pick(&self, cells: &[usize]) -> Option<usize> {
+        // the uncolored cell with the fewest colors left, ties go to the one
+        // with the most neighbors
+        let mut best: Option<(u32, usize, usize)> = None;
+        for i in cells.iter().copied() {
+            if self.colors[i] != NONE {
+                continue;
+            }
+            let key = (
+                self.domains[i].count_ones(),
+                usize::MAX - self.neighbors(i).len(),
+                i,
+            );
+            if best.is_none_or(|b| (key.0, key.1) < (b.0, b.1)) {
+                best = Some(key);
+            }
+        }
+        best.map(|(_, _, i)| i)
+    }
+    pub fn color(
+        &mut self,
+        cells: &[usize],
+        colors: &mut [Option<usize>],
+        control: &Control,
+    ) -> (bool, usize, usize) {
+        // color the given cells with k colors, returns whether it worked and
+        // how many nodes and backtracks the search took (or false if control
+        // stopped it first)
+        self.stack.clear();
+        self.nodes = 0;
+        self.backtracks = 0;
+        let mut used = 0;
+        for i in cells.iter().copied() {
+            if let Some(c) = colors[i] {
+                // keep colors that were already given
+                if c >= self.k || self.domains[i] & (1 << c) == 0 {
+                    return (false, 0, 0);
+                }
+                self.assign(i, c);
+                used = used.max(c + 1);
+            }
+        }
+        let res = self.search(cells, used as u32, control);
+        for i in cells.iter().copied() {
+            if res && colors[i].is_none() {
+                colors[i] = Some(self.colors[i] as usize);
+            }
+        }
+        (res, self.nodes, self.backtracks)
+    }
+    fn search(&mut self, cells: &[usize], used: u32, control: &Control) -> bool {
+        let mut descend = true;
+        let mut used = used;
+        loop {
+            if descend {
+                // go one level deeper with the most constrained cell
+                let i = match self.pick(cells) {
+                    Some(i) => i,
+                    None => return true, // everything is colored
+                };
+                // colors are interchangeable, so only one unused color needs trying
+                let fresh = if used as usize + 1 >= self.k {
+                    u64::MAX
+                } else {
+                    (1 << (used + 1)) - 1
+                };
+                self.stack.push(Frame {
+                    cell: i as u32,
+                    options: self.domains[i] & fresh,
+                    color: NONE,
+                    used,
+                });
+            }
+            let frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => return false, // every option of the first cell failed
+            };
+            let (i, previous) = (frame.cell as usize, frame.color);
+            used = frame.used;
+            if frame.options == 0 {
+                // nothing left for this cell, go back up
+                self.stack.pop();
+                if previous != NONE {
+                    self.unassign(i, previous as usize);
+                }
+                self.backtracks += 1;
+                descend = false;
+                continue;
+            }
+            let c = frame.options.trailing_zeros();
+            frame.options &= !(1 << c);
+            frame.color = c;
+            if previous != NONE {
+                self.unassign(i, previous as usize);
+            }
+            self.nodes += 1;
+            let stop = control.poll(self.nodes, || {
+                self.colors
+                    .iter()
+                    .map(|c| (*c != NONE).then_some(*c as usize))
+                    .collect()
+            });
+            if stop {
+                return false;
+            }
+            self.assign(i, c as usize);
+            used = used.max(c + 1);
+            // only go deeper if no neighbor ran out of colors
+            descend = !self.wiped_out(i);
+        }
+    }
+}
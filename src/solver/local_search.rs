@@ -0,0 +1,121 @@
+use crate::cell::Map;
+
+use super::{by_component, control::Control, welsh_powell, Solution, Solver};
+
+pub struct LocalSearch {
+    pub max_steps: usize, // how many recolorings to try per component before giving up
+    pub seed: u64,        // the seed for the random choices, so runs can be repeated
+}
+
+impl Default for LocalSearch {
+    fn default() -> LocalSearch {
+        LocalSearch {
+            max_steps: 100_000,
+            seed: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl Solver for LocalSearch {
+    fn name(&self) -> &'static str {
+        "Local search"
+    }
+    fn exact(&self) -> bool {
+        false
+    }
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution {
+        let mut rng = self.seed;
+        by_component(map, k, self.exact(), control, |cells, colors| {
+            color(map, cells, k, colors, self.max_steps, &mut rng, control)
+        })
+    }
+}
+
+fn next(rng: &mut u64) -> usize {
+    // xorshift, good enough to break ties and pick random cells
+    *rng ^= *rng << 13;
+    *rng ^= *rng >> 7;
+    *rng ^= *rng << 17;
+    *rng as usize
+}
+
+fn conflicts(map: &Map, colors: &[Option<usize>], i: usize, c: usize) -> usize {
+    // how many neighbors of i have the color c
+    map.0[i]
+        .connections
+        .iter()
+        .filter(|n| colors[**n] == Some(c))
+        .count()
+}
+
+pub fn color(
+    map: &Map,
+    cells: &[usize],
+    k: usize,
+    colors: &mut [Option<usize>],
+    max_steps: usize,
+    rng: &mut u64,
+    control: &Control,
+) -> (bool, usize, usize) {
+    // min-conflicts search: begin with a greedy coloring, give the cells it
+    // could not color any color, then keep moving a conflicting cell to the
+    // color that clashes with the fewest neighbors.
+    if k == 0 {
+        return (cells.is_empty(), 0, 0);
+    }
+    let fixed: Vec<bool> = colors.iter().map(|c| c.is_some()).collect();
+    let (done, mut nodes, _) = welsh_powell::color(map, cells, k, colors);
+    if done {
+        return (true, nodes, 0);
+    }
+    for i in cells {
+        if colors[*i].is_none() {
+            colors[*i] = Some(next(rng) % k);
+        }
+    }
+    for _ in 0..max_steps {
+        if control.stopped() {
+            break;
+        }
+        let conflicted: Vec<usize> = cells
+            .iter()
+            .copied()
+            .filter(|i| !fixed[*i] && conflicts(map, colors, *i, colors[*i].unwrap()) > 0)
+            .collect();
+        if conflicted.is_empty() {
+            return (true, nodes, 0);
+        }
+        nodes += 1;
+        let i = conflicted[next(rng) % conflicted.len()];
+        let current = colors[i].unwrap();
+        // every color that clashes the least, other than the current one
+        let mut best = Vec::new();
+        let mut least = usize::MAX;
+        for c in (0..k).filter(|c| *c != current) {
+            let n = conflicts(map, colors, i, c);
+            if n < least {
+                least = n;
+                best.clear();
+            }
+            if n == least {
+                best.push(c);
+            }
+        }
+        // sometimes make a random move instead, so it doesnt get stuck
+        colors[i] = if best.is_empty() || next(rng).is_multiple_of(10) {
+            Some(next(rng) % k)
+        } else {
+            Some(best[next(rng) % best.len()])
+        };
+    }
+    // give up, and only keep the cells that dont clash with anything
+    let clashing: Vec<usize> = cells
+        .iter()
+        .copied()
+        .filter(|i| !fixed[*i] && conflicts(map, colors, *i, colors[*i].unwrap()) > 0)
+        .collect();
+    for i in clashing {
+        colors[i] = None;
+    }
+    (false, nodes, 0)
+}
@@ -0,0 +1,275 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    cell::{ColorReport, ComponentResult, Map},
+    pin,
+};
+
+pub mod background;
+pub mod control;
+pub mod dfs;
+pub mod dsatur;
+pub mod engine;
+pub mod local_search;
+pub mod welsh_powell;
+
+use control::Control;
+
+pub trait Solver {
+    // color the map with k colors, giving up when control says the search should stop
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution;
+    fn solve(&self, map: &Map, k: usize) -> Solution {
+        // color the map with k colors, however long it takes
+        self.solve_with(map, k, &Control::default())
+    }
+    fn name(&self) -> &'static str; // the name shown to the user
+    fn exact(&self) -> bool; // true if failing proves the map cant be colored with k colors
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Solution {
+    pub colors: Vec<Option<usize>>, // the color of every cell (None where the solver gave up)
+    pub report: ColorReport,        // whether it worked and how much work it took
+    pub elapsed: Duration,          // how long the solver ran for
+}
+
+impl Solution {
+    pub fn apply(&self, map: &mut Map) {
+        // copy the colors over to the map
+        for (cell, color) in map.0.iter_mut().zip(self.colors.iter()) {
+            cell.color = *color;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Strategy {
+    Dfs,         // the original recursive search through the neighbors of each cell
+    WelshPowell, // greedy, one color at a time over the cells sorted by degree
+    Dsatur,      // always color the cell with the most differently colored neighbors next
+    LocalSearch, // start from a greedy coloring and repair conflicts one cell at a time
+    Engine,      // DSATUR without recursion, using bitsets for the colors left to each cell
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 5] = [
+        Strategy::Dfs,
+        Strategy::WelshPowell,
+        Strategy::Dsatur,
+        Strategy::LocalSearch,
+        Strategy::Engine,
+    ];
+
+    pub fn solver(&self) -> Box<dyn Solver> {
+        // the solver that implements this strategy
+        match self {
+            Strategy::Dfs => Box::new(dfs::Dfs),
+            Strategy::WelshPowell => Box::new(welsh_powell::WelshPowell),
+            Strategy::Dsatur => Box::new(dsatur::Dsatur),
+            Strategy::LocalSearch => Box::new(local_search::LocalSearch::default()),
+            Strategy::Engine => Box::new(engine::Engine),
+        }
+    }
+    pub fn name(&self) -> &'static str {
+        self.solver().name()
+    }
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        // find a strategy by its name, ignoring case, spaces and dashes
+        let simplify = |s: &str| {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        };
+        Strategy::ALL
+            .into_iter()
+            .find(|s| simplify(s.name()) == simplify(name))
+            .or(match simplify(name).as_str() {
+                "engine" => Some(Strategy::Engine),
+                _ => None,
+            })
+    }
+}
+
+pub fn by_component<F>(
+    map: &Map,
+    k: usize,
+    exact: bool,
+    control: &Control,
+    mut color: F,
+) -> Solution
+where
+    F: FnMut(&[usize], &mut Vec<Option<usize>>) -> (bool, usize, usize),
+{
+    // run a solver on every connected component on its own.
+    // color gets the cells of the component and the colors so far, and returns
+    // whether it worked and how many nodes and backtracks it took. once the
+    // control says to stop the remaining components are left uncolored.
+    // exact says if a component that didnt work proves the map cant be colored
+    let start = Instant::now();
+    // pinned cells always start with their color
+    let mut colors: Vec<Option<usize>> = map.0.iter().map(|c| c.pinned.or(c.color)).collect();
+    let mut report = ColorReport {
+        colored: true,
+        ..Default::default()
+    };
+    if !pin::problems(map, k).is_empty() {
+        // no point searching if the pins already clash
+        report.colored = false;
+        return Solution {
+            colors,
+            report,
+            elapsed: start.elapsed(),
+        };
+    }
+    for cells in map.components() {
+        let (colored, nodes, backtracks) = if control.stopped() {
+            (false, 0, 0)
+        } else {
+            color(&cells, &mut colors)
+        };
+        report.colored &= colored;
+        report.iterations += nodes;
+        report.backtracks += backtracks;
+        report.components.push(ComponentResult {
+            cells,
+            colored,
+            iterations: nodes,
+            backtracks,
+        });
+    }
+    // if it had to stop, not being colored doesnt mean it cant be
+    report.stopped = !report.colored && control.stopped();
+    // and a solver that isnt exact only knows it didnt find a coloring
+    report.gave_up = !report.colored && !report.stopped && !exact;
+    Solution {
+        colors,
+        report,
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{complete, graph, welsh_powell_trap};
+
+    fn random_graph(n: usize, percent: u64, seed: &mut u64) -> Map {
+        // every pair is linked with the given chance
+        let mut edges = Vec::new();
+        for a in 0..n {
+            for b in a + 1..n {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                if *seed % 100 < percent {
+                    edges.push((a, b));
+                }
+            }
+        }
+        graph(n, &edges)
+    }
+
+    fn solvers() -> Vec<Box<dyn Solver>> {
+        // every solver, with local search kept short since it runs until its steps run out when it fails
+        Strategy::ALL
+            .iter()
+            .map(|s| match s {
+                Strategy::LocalSearch => Box::new(local_search::LocalSearch {
+                    max_steps: 1000,
+                    ..Default::default()
+                }),
+                _ => s.solver(),
+            })
+            .collect()
+    }
+
+    fn check(map: &Map, k: usize, chromatic: usize) {
+        for solver in solvers() {
+            let solution = solver.solve(map, k);
+            let report = &solution.report;
+            if report.colored {
+                // a coloring has to be complete and valid
+                assert!(
+                    k >= chromatic,
+                    "{} colored with too few colors",
+                    solver.name()
+                );
+                let mut colored = map.clone();
+                solution.apply(&mut colored);
+                for (i, cell) in colored.0.iter().enumerate() {
+                    assert!(cell.color.is_some_and(|c| c < k), "{}", solver.name());
+                    assert!(colored.is_valid_at(i), "{}", solver.name());
+                    if let Some(pin) = cell.pinned {
+                        assert_eq!(cell.color, Some(pin), "{} moved a pin", solver.name());
+                    }
+                }
+            } else if solver.exact() {
+                assert!(k < chromatic, "{} missed a coloring", solver.name());
+                assert!(!report.unknown(), "{}", solver.name());
+            } else {
+                // a solver that isnt exact may fail, but only claims it cant be done when it is sure
+                assert!(
+                    report.unknown() || k < chromatic,
+                    "{} claimed a proof",
+                    solver.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn solvers_agree_on_small_graphs() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        for n in 1..=9 {
+            for percent in [20, 40, 60, 80] {
+                let map = random_graph(n, percent, &mut seed);
+                let chromatic = map.chromatic_number().colors;
+                for k in 1..=chromatic + 1 {
+                    check(&map, k, chromatic);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn greedy_failure_is_unknown() {
+        let map = welsh_powell_trap();
+        let solution = Strategy::WelshPowell.solver().solve(&map, 3);
+        assert!(!solution.report.colored);
+        assert!(solution.report.gave_up);
+        check(&map, 3, 3);
+    }
+
+    #[test]
+    fn pins_are_kept() {
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for n in 2..=8 {
+            let mut map = random_graph(n, 50, &mut seed);
+            map.0[0].pinned = Some(1);
+            map.0[n - 1].pinned = map.0[n - 1]
+                .connections
+                .contains(&0)
+                .then_some(2)
+                .or(Some(1));
+            // the pins are part of the coloring, so find the truth with them
+            let chromatic = (1..=n + 2)
+                .find(|k| Strategy::Dsatur.solver().solve(&map, *k).report.colored)
+                .unwrap();
+            for k in 1..=chromatic + 1 {
+                check(&map, k, chromatic);
+            }
+        }
+    }
+
+    #[test]
+    fn engine_handles_more_than_64_colors() {
+        // a complete graph needs exactly one color per region
+        let n = 66;
+        let map = complete(n);
+        let engine = Strategy::Engine.solver();
+        assert!(engine.solve(&map, n).report.colored);
+        let solution = engine.solve(&map, n - 1);
+        assert!(!solution.report.colored && !solution.report.unknown());
+    }
+}
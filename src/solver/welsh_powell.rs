@@ -0,0 +1,46 @@
+use crate::cell::Map;
+
+use super::{by_component, control::Control, Solution, Solver};
+
+pub struct WelshPowell; // greedy coloring, never takes a color back
+
+impl Solver for WelshPowell {
+    fn name(&self) -> &'static str {
+        "Welsh-Powell"
+    }
+    fn exact(&self) -> bool {
+        false
+    }
+    fn solve_with(&self, map: &Map, k: usize, control: &Control) -> Solution {
+        // greedy is quick enough that it never needs to stop early
+        by_component(map, k, self.exact(), control, |cells, colors| {
+            color(map, cells, k, colors)
+        })
+    }
+}
+
+pub fn color(
+    map: &Map,
+    cells: &[usize],
+    k: usize,
+    colors: &mut [Option<usize>],
+) -> (bool, usize, usize) {
+    // go through the cells from the most to the least neighbors, and hand out
+    // one color at a time to every cell that can still take it
+    let mut order = cells.to_vec();
+    order.sort_by_key(|i| std::cmp::Reverse(map.0[*i].connections.len()));
+    let mut nodes = 0;
+    for c in 0..k {
+        for i in order.iter() {
+            if colors[*i].is_some() {
+                continue;
+            }
+            nodes += 1;
+            if map.0[*i].connections.iter().all(|n| colors[*n] != Some(c)) {
+                colors[*i] = Some(c);
+            }
+        }
+    }
+    // it only worked if every cell got one of the k colors
+    (cells.iter().all(|i| colors[*i].is_some()), nodes, 0)
+}
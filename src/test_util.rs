@@ -0,0 +1,46 @@
+// small maps the tests of several modules share
+use crate::cell::Map;
+
+pub fn graph(n: usize, edges: &[(usize, usize)]) -> Map {
+    // a map with the regions 0 to n-1 and the given links
+    let mut map = Map::default();
+    map.add_names((0..n).map(|i| i.to_string()).collect());
+    for (a, b) in edges {
+        map.0[*a].connections.push(*b);
+        map.0[*b].connections.push(*a);
+    }
+    map
+}
+
+pub fn complete(n: usize) -> Map {
+    // n regions that all border each other, so it takes n colors
+    let edges: Vec<(usize, usize)> = (0..n)
+        .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+        .collect();
+    graph(n, &edges)
+}
+
+pub fn welsh_powell_trap() -> Map {
+    // welsh-powell cant 3 color this, but it can be done
+    graph(
+        10,
+        &[
+            (0, 1),
+            (0, 2),
+            (0, 4),
+            (0, 5),
+            (0, 6),
+            (1, 4),
+            (1, 5),
+            (1, 9),
+            (2, 7),
+            (3, 4),
+            (3, 6),
+            (4, 8),
+            (4, 9),
+            (5, 6),
+            (6, 9),
+            (8, 9),
+        ],
+    )
+}
@@ -0,0 +1,61 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Assign(usize, usize), // a cell got a color
+    Conflict(usize),      // a cell has no color left that none of its neighbors have
+    Backtrack(usize),     // a cell lost its color, since nothing worked with it
+}
+
+impl Step {
+    pub fn cell(&self) -> usize {
+        // the cell the step is about
+        match self {
+            Step::Assign(i, _) | Step::Conflict(i) | Step::Backtrack(i) => *i,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    pub truncated: bool, // if the search wanted to take more steps than are kept, and stopped
+    limit: usize,        // how many steps to keep, 0 to not record at all
+}
+
+impl Trace {
+    pub fn off() -> Trace {
+        // a trace that doesnt record anything, for normal solving
+        Trace::default()
+    }
+    pub fn on(limit: usize) -> Trace {
+        // a trace that records the first limit steps of the search
+        Trace {
+            limit,
+            ..Default::default()
+        }
+    }
+    pub fn push(&mut self, step: Step) {
+        if self.steps.len() < self.limit {
+            self.steps.push(step);
+        } else if self.limit > 0 {
+            self.truncated = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::complete;
+
+    #[test]
+    fn search_stops_when_the_trace_is_full() {
+        // 6 regions that all border each other cant be colored with 5 colors,
+        // and the search would take far more than 50 steps to find that out
+        let map = complete(6);
+        let (full, _) = map.trace(5, 1_000_000);
+        let (report, trace) = map.trace(5, 50);
+        assert!(trace.truncated);
+        assert_eq!(trace.steps.len(), 50);
+        assert!(report.stopped && report.unknown());
+        assert!(report.iterations < full.iterations);
+    }
+}
@@ -1,9 +1,9 @@
 use std::path::Path;
 
-pub fn get_next_file_path() -> String {
+pub fn get_next_file_path(extension: &str) -> String {
     let mut i = 1;
-    while Path::new(&format!("./maps/{}.toml", i)).exists() {
+    while Path::new(&format!("./maps/{}.{}", i, extension)).exists() {
         i += 1;
     }
-    return format!("./maps/{}.toml", i);
+    format!("./maps/{}.{}", i, extension)
 }
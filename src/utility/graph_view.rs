@@ -0,0 +1,153 @@
+use egui::{Align2, Color32, FontId, Pos2, Response, Sense, Stroke, Ui, Vec2};
+use map_coloring::{
+    cell::Map,
+    layout::{layout, Point},
+};
+
+const NODE_SIZE: f32 = 16.; // the radius of a cell on the canvas
+const HIGHLIGHT: Color32 = Color32::from_rgb(255, 200, 0);
+
+#[derive(Clone)]
+pub struct GraphView {
+    pub positions: Vec<Point>, // where every cell is, from 0 to 1 on both axes
+    offset: Vec2,              // how far the view is panned
+    zoom: f32,
+    panning: bool,            // if the current drag started on empty space
+    pub focus: Option<usize>, // a cell to point out, like the one a replay is at
+}
+
+impl Default for GraphView {
+    fn default() -> GraphView {
+        GraphView {
+            positions: Vec::new(),
+            offset: Vec2::ZERO,
+            zoom: 1.,
+            panning: false,
+            focus: None,
+        }
+    }
+}
+
+impl GraphView {
+    pub fn relayout(&mut self, map: &Map) {
+        self.positions = layout(map);
+    }
+    fn scale(&self, response: &Response) -> f32 {
+        (response.rect.width().min(response.rect.height()) - 4. * NODE_SIZE) * self.zoom
+    }
+    pub fn screen_pos(&self, response: &Response, p: Point) -> Pos2 {
+        let scale = self.scale(response);
+        response.rect.center() + Vec2::new(p.0 - 0.5, p.1 - 0.5) * scale + self.offset
+    }
+    pub fn map_pos(&self, response: &Response, pos: Pos2) -> Point {
+        let v = (pos - response.rect.center() - self.offset) / self.scale(response);
+        (v.x + 0.5, v.y + 0.5)
+    }
+    pub fn reset_view(&mut self) {
+        self.offset = Vec2::ZERO;
+        self.zoom = 1.;
+    }
+    pub fn cell_at(&self, response: &Response, pos: Pos2) -> Option<usize> {
+        // the cell under pos, if there is one
+        (0..self.positions.len())
+            .map(|i| {
+                (
+                    i,
+                    self.screen_pos(response, self.positions[i]).distance(pos),
+                )
+            })
+            .filter(|(_, d)| *d <= NODE_SIZE)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+    pub fn show(&mut self, ui: &mut Ui, map: &Map, size: Vec2) -> Response {
+        // draw the cells as colored circles and the links as lines between them.
+        // scroll to zoom and drag empty space to pan
+        if self.positions.len() != map.0.len() {
+            self.relayout(map);
+        }
+        let (response, painter) = ui.allocate_painter(size, Sense::click_and_drag());
+        painter.rect_filled(response.rect, 5., Color32::from_gray(235));
+        if response.drag_started() {
+            // drags that start on a cell are left to the caller
+            let origin = ui.input().pointer.press_origin();
+            self.panning = origin.is_some_and(|p| self.cell_at(&response, p).is_none());
+        }
+        if response.dragged() && self.panning {
+            self.offset += response.drag_delta();
+        }
+        if let Some(pointer) = response.hover_pos() {
+            // zoom around the pointer, so the spot under it stays put
+            let input = ui.input();
+            let factor = input.zoom_delta() * (input.scroll_delta.y / 200.).exp();
+            if factor != 1. {
+                let zoom = (self.zoom * factor).clamp(0.2, 10.);
+                let from_center = pointer - response.rect.center();
+                self.offset = from_center - (from_center - self.offset) * (zoom / self.zoom);
+                self.zoom = zoom;
+            }
+        }
+        let hovered = response
+            .hover_pos()
+            .and_then(|p| self.cell_at(&response, p));
+        let at = |i: usize| self.screen_pos(&response, self.positions[i]);
+
+        for (i, cell) in map.0.iter().enumerate() {
+            // every link is listed by both cells, only draw it once
+            for j in cell.connections.iter().filter(|j| **j > i) {
+                let conflict = cell.color.is_some() && cell.color == map.0[*j].color;
+                let stroke = if conflict {
+                    // two neighbors with the same color
+                    Stroke::new(4., Color32::RED)
+                } else if hovered == Some(i) || hovered == Some(*j) {
+                    Stroke::new(3., HIGHLIGHT)
+                } else {
+                    Stroke::new(1.5, Color32::GRAY)
+                };
+                painter.line_segment([at(i), at(*j)], stroke);
+            }
+        }
+        for (i, cell) in map.0.iter().enumerate() {
+            let color = cell.color();
+            // the hovered cell and its neighbors get a ring around them,
+            // and cells that share a color with a neighbor a red one
+            let ring = match hovered {
+                _ if self.focus == Some(i) => Stroke::new(5., Color32::from_rgb(0, 100, 200)),
+                Some(h) if h == i || map.0[h].connections.contains(&i) => {
+                    Stroke::new(3., HIGHLIGHT)
+                }
+                _ if cell.color.is_some() && !map.is_valid_at(i) => Stroke::new(3., Color32::RED),
+                _ => Stroke::new(1., Color32::BLACK),
+            };
+            painter.circle(at(i), NODE_SIZE, Color32::from(color), ring);
+            if cell.pinned.is_some() {
+                // a pin in the corner for cells that have to keep their color
+                let pin = at(i) + Vec2::new(NODE_SIZE * 0.7, -NODE_SIZE * 0.7);
+                painter.circle(pin, 5., Color32::BLACK, Stroke::new(1.5, Color32::WHITE));
+            }
+            painter.text(
+                at(i) + Vec2::new(0., NODE_SIZE + 2.),
+                Align2::CENTER_TOP,
+                cell.label.as_ref().unwrap_or(&cell.name),
+                FontId::proportional(13.),
+                Color32::BLACK,
+            );
+        }
+        match hovered {
+            Some(h) => {
+                let cell = &map.0[h];
+                let neighbors: Vec<&str> = cell
+                    .connections
+                    .iter()
+                    .map(|j| map.0[*j].name.as_str())
+                    .collect();
+                let mut text = format!("{} borders {}", cell.name, neighbors.join(", "));
+                if let Some(description) = &cell.description {
+                    text = format!("{}\n{}", description, text);
+                }
+                response.on_hover_text(text)
+            }
+            None => response,
+        }
+    }
+}
@@ -1 +1,3 @@
 pub mod file;
+pub mod graph_view;
+pub mod replay;
@@ -0,0 +1,119 @@
+use egui::Ui;
+use map_coloring::{
+    cell::Map,
+    palette::color_name,
+    trace::{Step, Trace},
+};
+
+#[derive(Clone)]
+pub struct Replay {
+    pub map: Map, // the map as it is after the steps played so far
+    trace: Trace,
+    position: usize, // how many steps were played
+    playing: bool,
+    speed: f32,    // steps per second
+    progress: f32, // how much of the next step has passed
+}
+
+impl Replay {
+    pub fn new(map: &Map, trace: Trace) -> Replay {
+        // start from a blank map but for the pins, the trace has every color the search gives
+        let mut map = map.clone();
+        map.reset_colors();
+        Replay {
+            map,
+            trace,
+            position: 0,
+            playing: true,
+            speed: 5.,
+            progress: 0.,
+        }
+    }
+    pub fn current(&self) -> Option<Step> {
+        // the step that was played last
+        self.position.checked_sub(1).map(|i| self.trace.steps[i])
+    }
+    fn step(&mut self) {
+        // play the next step, if there is one
+        let step = match self.trace.steps.get(self.position) {
+            Some(step) => *step,
+            None => {
+                self.playing = false;
+                return;
+            }
+        };
+        match step {
+            Step::Assign(i, c) => self.map.0[i].color = Some(c),
+            Step::Backtrack(i) => self.map.0[i].color = None,
+            Step::Conflict(_) => {}
+        }
+        self.position += 1;
+    }
+    pub fn describe(&self) -> String {
+        // what the last step did, in words
+        let name = |i: usize| self.map.0[i].name.clone();
+        match self.current() {
+            None => "The search hasnt started yet".to_string(),
+            Some(Step::Assign(i, c)) => format!("{} is colored {}", name(i), color_name(c)),
+            Some(Step::Conflict(i)) => {
+                format!("{} has no color left that its neighbors dont have", name(i))
+            }
+            Some(Step::Backtrack(i)) => {
+                format!(
+                    "Nothing worked with {}, so its color is taken back",
+                    name(i)
+                )
+            }
+        }
+    }
+    pub fn update(&mut self, ui: &mut Ui) {
+        // play as many steps as the time since the last frame allows
+        if !self.playing {
+            return;
+        }
+        self.progress += ui.input().unstable_dt.min(0.1) * self.speed;
+        while self.progress >= 1. && self.playing {
+            self.progress -= 1.;
+            self.step();
+        }
+        ui.ctx().request_repaint();
+    }
+    pub fn controls(&mut self, ui: &mut Ui) -> bool {
+        // the play, pause, step and speed controls, gives true when the replay should close
+        let mut close = false;
+        ui.horizontal(|ui| {
+            let label = if self.playing { "Pause" } else { "Play" };
+            if ui.button(label).clicked() {
+                self.playing = !self.playing;
+            }
+            if ui.button("Step").clicked() {
+                self.playing = false;
+                self.step();
+            }
+            if ui.button("Restart").clicked() {
+                let speed = self.speed;
+                *self = Replay::new(&self.map, self.trace.clone());
+                self.speed = speed;
+            }
+            ui.add(
+                egui::Slider::new(&mut self.speed, 1.0..=500.0)
+                    .logarithmic(true)
+                    .text("steps per second"),
+            );
+            if ui.button("Close").clicked() {
+                close = true;
+            }
+        });
+        let mut status = format!(
+            "Step {} of {}: {}",
+            self.position,
+            self.trace.steps.len(),
+            self.describe()
+        );
+        if self.trace.truncated && self.position == self.trace.steps.len() {
+            status += " (the search stopped here, since only this many steps are kept)";
+        }
+        ui.label(status);
+        close
+    }
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+
+use crate::{cell::Map, error::MapError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Issue {
+    AsymmetricLink { from: String, to: String }, // from lists to, but to doesnt list from
+    SelfLoop(String),                            // a region lists itself as a neighbor
+    DuplicateLink { from: String, to: String },  // from lists to more than once
+    DuplicateName(String),                       // two regions share the same name
+}
+
+impl Issue {
+    pub fn repairable(&self) -> bool {
+        // everything but duplicate names can be fixed without guessing
+        !matches!(self, Issue::DuplicateName(_))
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // say it the same way as the error it turns into
+        MapError::from(self.clone()).fmt(f)
+    }
+}
+
+impl From<Issue> for MapError {
+    fn from(issue: Issue) -> MapError {
+        match issue {
+            Issue::AsymmetricLink { from, to } => MapError::AsymmetricLink { from, to },
+            Issue::SelfLoop(name) => MapError::SelfLoop(name),
+            Issue::DuplicateLink { from, to } => MapError::DuplicateLink { from, to },
+            Issue::DuplicateName(name) => MapError::DuplicateRegion(name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>, // every problem that was found, in map order
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl Map {
+    pub fn validation_report(&self) -> ValidationReport {
+        // go through the whole map and list every problem instead of stopping at the first
+        let mut report = ValidationReport::default();
+        for (i, cell) in self.0.iter().enumerate() {
+            if self.0[..i].iter().any(|c| c.name == cell.name)
+                && !self.0[i + 1..].iter().any(|c| c.name == cell.name)
+            {
+                // only report each name once, at its last occurrence
+                report.issues.push(Issue::DuplicateName(cell.name.clone()));
+            }
+        }
+        for (i, cell) in self.0.iter().enumerate() {
+            for (j, conn) in cell.connections.iter().enumerate() {
+                let other = &self.0[*conn];
+                if *conn == i {
+                    report.issues.push(Issue::SelfLoop(cell.name.clone()));
+                } else if cell.connections[..j].contains(conn) {
+                    report.issues.push(Issue::DuplicateLink {
+                        from: cell.name.clone(),
+                        to: other.name.clone(),
+                    });
+                } else if !other.connections.contains(&i) {
+                    report.issues.push(Issue::AsymmetricLink {
+                        from: cell.name.clone(),
+                        to: other.name.clone(),
+                    });
+                }
+            }
+        }
+        report
+    }
+    pub fn checked(mut self, repair: bool) -> Result<(Map, ValidationReport), MapError> {
+        // with repair on, link problems are fixed and returned as warnings,
+        // otherwise the first problem is the error
+        if !repair {
+            self.check()?;
+            return Ok((self, ValidationReport::default()));
+        }
+        let report = self.repair();
+        // names cant be repaired, so those still fail
+        if let Some(issue) = report.issues.iter().find(|i| !i.repairable()) {
+            return Err(issue.clone().into());
+        }
+        Ok((self, report))
+    }
+    pub fn repair(&mut self) -> ValidationReport {
+        // fix every link problem: self loops and repeated links are dropped and
+        // one sided links are added to the other side as well.
+        // returns what was wrong before the repair.
+        let report = self.validation_report();
+        for i in 0..self.0.len() {
+            let mut seen = Vec::new();
+            for conn in self.0[i].connections.clone() {
+                if conn != i && !seen.contains(&conn) {
+                    seen.push(conn);
+                }
+            }
+            self.0[i].connections = seen;
+        }
+        for i in 0..self.0.len() {
+            for conn in self.0[i].connections.clone() {
+                if !self.0[conn].connections.contains(&i) {
+                    self.0[conn].connections.push(i);
+                }
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::graph;
+
+    fn broken() -> Map {
+        // 0 links 1 only one way, 1 links itself and 2 lists 0 twice
+        let mut map = graph(3, &[(0, 2)]);
+        map.0[0].connections.push(1);
+        map.0[1].connections.push(1);
+        map.0[2].connections.push(0);
+        map
+    }
+
+    #[test]
+    fn every_link_problem_is_reported() {
+        let issues = broken().validation_report().issues;
+        assert_eq!(
+            issues,
+            vec![
+                Issue::AsymmetricLink {
+                    from: "0".to_string(),
+                    to: "1".to_string()
+                },
+                Issue::SelfLoop("1".to_string()),
+                Issue::DuplicateLink {
+                    from: "2".to_string(),
+                    to: "0".to_string()
+                },
+            ]
+        );
+        assert!(graph(3, &[(0, 1), (1, 2)]).validation_report().is_valid());
+    }
+
+    #[test]
+    fn duplicate_names_are_reported_once() {
+        let mut map = graph(4, &[]);
+        for i in 1..4 {
+            map.0[i].name = "same".to_string();
+        }
+        assert_eq!(
+            map.validation_report().issues,
+            vec![Issue::DuplicateName("same".to_string())]
+        );
+        assert!(map.checked(true).is_err());
+    }
+
+    #[test]
+    fn repair_makes_links_two_sided() {
+        let mut map = broken();
+        let report = map.repair();
+        assert_eq!(report.issues.len(), 3);
+        assert!(map.validation_report().is_valid());
+        assert_eq!(map.0[0].connections, vec![2, 1]);
+        assert_eq!(map.0[1].connections, vec![0]);
+        assert_eq!(map.0[2].connections, vec![0]);
+    }
+
+    #[test]
+    fn only_repairs_when_asked() {
+        assert!(broken().checked(false).is_err());
+        let (map, report) = broken().checked(true).unwrap();
+        assert!(map.validation_report().is_valid());
+        assert!(report.issues.iter().all(|i| i.repairable()));
+    }
+}